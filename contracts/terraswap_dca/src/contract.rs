@@ -0,0 +1,398 @@
+use crate::error::ContractError;
+use crate::state::{schedules, Config, Schedule, CONFIG, LAST_SCHEDULE_ID};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdResult, Uint128, WasmMsg,
+};
+
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::dca::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ScheduleResponse, SchedulesResponse,
+};
+use terraswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+use terraswap::util::migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:terraswap-dca";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            terraswap_router: deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(&msg.terraswap_router)?.as_str())?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::CreateSchedule {
+            offer_asset,
+            interval,
+            amount_per_execution,
+            operations,
+            minimum_receive,
+        } => {
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            offer_asset.assert_sent_native_token_balance(&info)?;
+            create_schedule(
+                deps,
+                env,
+                info.sender,
+                offer_asset,
+                interval,
+                amount_per_execution,
+                operations,
+                minimum_receive,
+            )
+        }
+        ExecuteMsg::CancelSchedule { id } => cancel_schedule(deps, info, id),
+        ExecuteMsg::Trigger { id } => trigger(deps, env, id),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::CreateSchedule {
+            interval,
+            amount_per_execution,
+            operations,
+            minimum_receive,
+        } => {
+            let owner = deps.api.addr_validate(&cw20_msg.sender)?;
+            create_schedule(
+                deps,
+                env,
+                owner,
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender.to_string(),
+                    },
+                    amount: cw20_msg.amount,
+                },
+                interval,
+                amount_per_execution,
+                operations,
+                minimum_receive,
+            )
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_schedule(
+    deps: DepsMut,
+    env: Env,
+    owner: Addr,
+    offer_asset: Asset,
+    interval: u64,
+    amount_per_execution: Uint128,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Uint128,
+) -> Result<Response, ContractError> {
+    if offer_asset.amount.is_zero() || amount_per_execution.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    // the swaps are permissionless, so they must not run without slippage protection
+    if minimum_receive.is_zero() {
+        return Err(ContractError::MissingMinimumReceive {});
+    }
+
+    if interval == 0 {
+        return Err(ContractError::InvalidZeroInterval {});
+    }
+
+    assert_route(&offer_asset.info, &operations)?;
+
+    let id = LAST_SCHEDULE_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LAST_SCHEDULE_ID.save(deps.storage, &id)?;
+
+    // the first swap can be triggered right away
+    schedules().save(
+        deps.storage,
+        id,
+        &Schedule {
+            id,
+            owner: deps.api.addr_canonicalize(owner.as_str())?,
+            offer_asset: offer_asset.to_raw(deps.api)?,
+            interval,
+            amount_per_execution,
+            operations,
+            minimum_receive,
+            next_execution: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_schedule"),
+        ("id", &id.to_string()),
+        ("owner", owner.as_str()),
+        ("offer_asset", &offer_asset.to_string()),
+        ("interval", &interval.to_string()),
+        ("amount_per_execution", &amount_per_execution.to_string()),
+    ]))
+}
+
+/// The route must start from the offer asset
+/// and every operation must offer the asset asked by the previous one
+fn assert_route(
+    offer_asset_info: &AssetInfo,
+    operations: &[SwapOperation],
+) -> Result<(), ContractError> {
+    if operations.is_empty() {
+        return Err(ContractError::NoSwapOperations {});
+    }
+
+    let mut prev_asset_info = offer_asset_info.clone();
+    for operation in operations {
+        match operation {
+            SwapOperation::TerraSwap {
+                offer_asset_info,
+                ask_asset_info,
+                ..
+            } => {
+                if *offer_asset_info != prev_asset_info {
+                    return Err(ContractError::InvalidRoute {
+                        reason: format!("expected offer asset {}", prev_asset_info),
+                    });
+                }
+
+                prev_asset_info = ask_asset_info.clone();
+            }
+        }
+    }
+
+    if prev_asset_info == *offer_asset_info {
+        return Err(ContractError::InvalidRoute {
+            reason: "must not end with the offer asset".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+// Only the schedule owner can execute it
+pub fn cancel_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let schedule: Schedule = schedules().load(deps.storage, id)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != schedule.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    schedules().remove(deps.storage, id)?;
+
+    let offer_asset = schedule.offer_asset.to_normal(deps.api)?;
+    Ok(Response::new()
+        .add_message(offer_asset.clone().into_msg(info.sender)?)
+        .add_attributes(vec![
+            ("action", "cancel_schedule"),
+            ("id", &id.to_string()),
+            ("refund_asset", &offer_asset.to_string()),
+        ]))
+}
+
+// Anyone can execute it once the interval has elapsed
+pub fn trigger(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut schedule: Schedule = schedules().load(deps.storage, id)?;
+
+    let now = env.block.time.seconds();
+    if now < schedule.next_execution {
+        return Err(ContractError::NotTriggerable {
+            next_execution: schedule.next_execution,
+        });
+    }
+
+    // the last execution swaps the remainder
+    let amount = std::cmp::min(schedule.amount_per_execution, schedule.offer_asset.amount);
+    schedule.offer_asset.amount = schedule.offer_asset.amount.checked_sub(amount)?;
+    schedule.next_execution = now + schedule.interval;
+
+    if schedule.offer_asset.amount.is_zero() {
+        schedules().remove(deps.storage, id)?;
+    } else {
+        schedules().save(deps.storage, id, &schedule)?;
+    }
+
+    let terraswap_router = deps.api.addr_humanize(&config.terraswap_router)?;
+    let owner = deps.api.addr_humanize(&schedule.owner)?;
+    let offer_asset = Asset {
+        info: schedule.offer_asset.info.to_normal(deps.api)?,
+        amount,
+    };
+
+    // minimum_receive is set for a whole tranche, scale it to the remainder
+    let minimum_receive = Some(
+        schedule
+            .minimum_receive
+            .multiply_ratio(amount, schedule.amount_per_execution),
+    );
+
+    let message = match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: terraswap_router.to_string(),
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations: schedule.operations,
+                minimum_receive,
+                to: Some(owner.to_string()),
+                deadline: None,
+                referral_address: None,
+                referral_commission: None,
+            })?,
+            funds: coins(amount.u128(), denom),
+        }),
+        AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: terraswap_router.to_string(),
+                amount,
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: schedule.operations,
+                    minimum_receive,
+                    to: Some(owner.to_string()),
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })?,
+            })?,
+            funds: vec![],
+        }),
+    };
+
+    Ok(Response::new().add_message(message).add_attributes(vec![
+        ("action", "trigger"),
+        ("id", &id.to_string()),
+        ("offer_asset", &offer_asset.to_string()),
+        ("remaining_amount", &schedule.offer_asset.amount.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Schedule { id } => to_binary(&query_schedule(deps, id)?),
+        QueryMsg::Schedules {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_schedules(deps, owner, start_after, limit)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let resp = ConfigResponse {
+        terraswap_router: deps
+            .api
+            .addr_humanize(&config.terraswap_router)?
+            .to_string(),
+    };
+
+    Ok(resp)
+}
+
+pub fn query_schedule(deps: Deps, id: u64) -> StdResult<ScheduleResponse> {
+    let schedule: Schedule = schedules().load(deps.storage, id)?;
+
+    schedule_to_response(deps.api, schedule)
+}
+
+pub fn query_schedules(
+    deps: Deps,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SchedulesResponse> {
+    let owner = deps.api.addr_canonicalize(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let schedules = schedules()
+        .idx
+        .owner
+        .prefix(owner.to_vec())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, schedule) = item?;
+            schedule_to_response(deps.api, schedule)
+        })
+        .collect::<StdResult<Vec<ScheduleResponse>>>()?;
+
+    Ok(SchedulesResponse { schedules })
+}
+
+fn schedule_to_response(api: &dyn Api, schedule: Schedule) -> StdResult<ScheduleResponse> {
+    Ok(ScheduleResponse {
+        id: schedule.id,
+        owner: api.addr_humanize(&schedule.owner)?.to_string(),
+        offer_asset: schedule.offer_asset.to_normal(api)?,
+        interval: schedule.interval,
+        amount_per_execution: schedule.amount_per_execution,
+        operations: schedule.operations,
+        minimum_receive: schedule.minimum_receive,
+        next_execution: schedule.next_execution,
+    })
+}
+
+const TARGET_CONTRACT_VERSION: &str = "0.1.0";
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    migrate_version(
+        deps,
+        TARGET_CONTRACT_VERSION,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+
+    Ok(Response::default())
+}
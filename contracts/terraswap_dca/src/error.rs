@@ -0,0 +1,32 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Must provide a minimum receive")]
+    MissingMinimumReceive {},
+
+    #[error("Interval must be greater than zero")]
+    InvalidZeroInterval {},
+
+    #[error("Must provide swap operations")]
+    NoSwapOperations {},
+
+    #[error("Invalid route: {reason}")]
+    InvalidRoute { reason: String },
+
+    #[error("Schedule can be triggered from {next_execution}")]
+    NotTriggerable { next_execution: u64 },
+}
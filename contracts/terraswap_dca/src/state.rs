@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use terraswap::asset::AssetRaw;
+use terraswap::router::SwapOperation;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub terraswap_router: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const LAST_SCHEDULE_ID: Item<u64> = Item::new("last_schedule_id");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Schedule {
+    pub id: u64,
+    pub owner: CanonicalAddr,
+    pub offer_asset: AssetRaw,
+    pub interval: u64,
+    pub amount_per_execution: Uint128,
+    pub operations: Vec<SwapOperation>,
+    pub minimum_receive: Uint128,
+    pub next_execution: u64,
+}
+
+pub struct ScheduleIndexes<'a> {
+    pub owner: MultiIndex<'a, Vec<u8>, Schedule, u64>,
+}
+
+impl<'a> IndexList<Schedule> for ScheduleIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Schedule>> + '_> {
+        let v: Vec<&dyn Index<Schedule>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn schedules<'a>() -> IndexedMap<'a, u64, Schedule, ScheduleIndexes<'a>> {
+    let indexes = ScheduleIndexes {
+        owner: MultiIndex::new(
+            |s: &Schedule| s.owner.to_vec(),
+            "schedules",
+            "schedules__owner",
+        ),
+    };
+
+    IndexedMap::new("schedules", indexes)
+}
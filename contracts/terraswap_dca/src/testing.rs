@@ -0,0 +1,439 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use terraswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, to_binary, BankMsg, CosmosMsg, OwnedDeps, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::dca::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, ScheduleResponse,
+    SchedulesResponse,
+};
+use terraswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.to_string(),
+    }
+}
+
+fn swap(offer_asset_info: AssetInfo, ask_asset_info: AssetInfo) -> SwapOperation {
+    SwapOperation::TerraSwap {
+        offer_asset_info,
+        ask_asset_info,
+        fee_tier: None,
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_router: "router0000".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps
+}
+
+fn create_msg(amount: u128, operations: Vec<SwapOperation>) -> ExecuteMsg {
+    ExecuteMsg::CreateSchedule {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(amount),
+        },
+        interval: 86400,
+        amount_per_execution: Uint128::from(400u128),
+        operations,
+        minimum_receive: Uint128::from(100u128),
+    }
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = setup();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            terraswap_router: "router0000".to_string(),
+        }
+    );
+}
+
+#[test]
+fn create_schedule() {
+    let mut deps = setup();
+
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, create_msg(1000, vec![]));
+    assert_eq!(res, Err(ContractError::NoSwapOperations {}));
+
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg(1000, vec![swap(native("uluna"), token("asset0000"))]),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidRoute {
+            reason: "expected offer asset uusd".to_string()
+        })
+    );
+
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg(
+            1000,
+            vec![
+                swap(native("uusd"), token("asset0000")),
+                swap(token("asset0000"), native("uusd")),
+            ],
+        ),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidRoute {
+            reason: "must not end with the offer asset".to_string()
+        })
+    );
+
+    let msg = ExecuteMsg::CreateSchedule {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(1000u128),
+        },
+        interval: 0,
+        amount_per_execution: Uint128::from(400u128),
+        operations: vec![swap(native("uusd"), token("asset0000"))],
+        minimum_receive: Uint128::from(100u128),
+    };
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidZeroInterval {}));
+
+    // permissionless swaps require slippage protection
+    let msg = ExecuteMsg::CreateSchedule {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(1000u128),
+        },
+        interval: 86400,
+        amount_per_execution: Uint128::from(400u128),
+        operations: vec![swap(native("uusd"), token("asset0000"))],
+        minimum_receive: Uint128::zero(),
+    };
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::MissingMinimumReceive {}));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(500u128),
+        msg: to_binary(&Cw20HookMsg::CreateSchedule {
+            interval: 3600,
+            amount_per_execution: Uint128::from(100u128),
+            operations: vec![swap(token("asset0000"), native("uusd"))],
+            minimum_receive: Uint128::zero(),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::MissingMinimumReceive {}));
+
+    let operations = vec![
+        swap(native("uusd"), native("uluna")),
+        swap(native("uluna"), token("asset0000")),
+    ];
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg(1000, operations.clone()),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "create_schedule"),
+            attr("id", "1"),
+            attr("owner", "addr0000"),
+            attr("offer_asset", "1000uusd"),
+            attr("interval", "86400"),
+            attr("amount_per_execution", "400"),
+        ]
+    );
+
+    // cw20 deposit
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(500u128),
+        msg: to_binary(&Cw20HookMsg::CreateSchedule {
+            interval: 3600,
+            amount_per_execution: Uint128::from(100u128),
+            operations: vec![swap(token("asset0000"), native("uusd"))],
+            minimum_receive: Uint128::from(50u128),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res: SchedulesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Schedules {
+                owner: "addr0000".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.schedules,
+        vec![
+            ScheduleResponse {
+                id: 1,
+                owner: "addr0000".to_string(),
+                offer_asset: Asset {
+                    info: native("uusd"),
+                    amount: Uint128::from(1000u128),
+                },
+                interval: 86400,
+                amount_per_execution: Uint128::from(400u128),
+                operations,
+                minimum_receive: Uint128::from(100u128),
+                next_execution: mock_env().block.time.seconds(),
+            },
+            ScheduleResponse {
+                id: 2,
+                owner: "addr0000".to_string(),
+                offer_asset: Asset {
+                    info: token("asset0000"),
+                    amount: Uint128::from(500u128),
+                },
+                interval: 3600,
+                amount_per_execution: Uint128::from(100u128),
+                operations: vec![swap(token("asset0000"), native("uusd"))],
+                minimum_receive: Uint128::from(50u128),
+                next_execution: mock_env().block.time.seconds(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn trigger() {
+    let mut deps = setup();
+
+    let operations = vec![swap(native("uusd"), token("asset0000"))];
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg(1000, operations.clone()),
+    )
+    .unwrap();
+
+    // anyone can trigger the first execution right away
+    let info = mock_info("keeper0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Trigger { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router0000".to_string(),
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations: operations.clone(),
+                minimum_receive: Some(Uint128::from(100u128)),
+                to: Some("addr0000".to_string()),
+                deadline: None,
+                referral_address: None,
+                referral_commission: None,
+            })
+            .unwrap(),
+            funds: coins(400, "uusd"),
+        }))]
+    );
+
+    // the interval has not elapsed
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86399);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Trigger { id: 1 },
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::NotTriggerable {
+            next_execution: mock_env().block.time.seconds() + 86400
+        })
+    );
+
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Trigger { id: 1 },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("remaining_amount", "200")));
+
+    // the last execution swaps the remainder and removes the schedule,
+    // the minimum receive is scaled to 100 * 200 / 400
+    env.block.time = env.block.time.plus_seconds(86400);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Trigger { id: 1 }).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router0000".to_string(),
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(Uint128::from(50u128)),
+                to: Some("addr0000".to_string()),
+                deadline: None,
+                referral_address: None,
+                referral_commission: None,
+            })
+            .unwrap(),
+            funds: coins(200, "uusd"),
+        }))]
+    );
+    assert!(res.attributes.contains(&attr("offer_asset", "200uusd")));
+    assert!(res.attributes.contains(&attr("remaining_amount", "0")));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Schedule { id: 1 });
+    assert!(res.is_err());
+}
+
+#[test]
+fn trigger_token_schedule() {
+    let mut deps = setup();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(500u128),
+        msg: to_binary(&Cw20HookMsg::CreateSchedule {
+            interval: 3600,
+            amount_per_execution: Uint128::from(100u128),
+            operations: vec![swap(token("asset0000"), native("uusd"))],
+            minimum_receive: Uint128::from(50u128),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("keeper0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Trigger { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "router0000".to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: vec![swap(token("asset0000"), native("uusd"))],
+                    minimum_receive: Some(Uint128::from(50u128)),
+                    to: Some("addr0000".to_string()),
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn cancel_schedule() {
+    let mut deps = setup();
+
+    let info = mock_info("addr0000", &coins(1000, "uusd"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg(1000, vec![swap(native("uusd"), token("asset0000"))]),
+    )
+    .unwrap();
+
+    let info = mock_info("keeper0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Trigger { id: 1 },
+    )
+    .unwrap();
+
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CancelSchedule { id: 1 },
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // the remaining deposit is refunded
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CancelSchedule { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![coin(600, "uusd")],
+        }))]
+    );
+}
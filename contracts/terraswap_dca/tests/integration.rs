@@ -0,0 +1,284 @@
+use cosmwasm_std::{to_binary, Addr, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::dca::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, ScheduleResponse};
+use terraswap::factory::{
+    ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg,
+};
+use terraswap::router::{InstantiateMsg as RouterInstantiateMsg, SwapOperation};
+use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+
+const OWNER: &str = "owner0000";
+const USER: &str = "addr0000";
+const KEEPER: &str = "keeper0000";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_token::contract::execute,
+        terraswap_token::contract::instantiate,
+        terraswap_token::contract::query,
+    ))
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_pair::contract::execute,
+            terraswap_pair::contract::instantiate,
+            terraswap_pair::contract::query,
+        )
+        .with_reply(terraswap_pair::contract::reply),
+    )
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_factory::contract::execute,
+            terraswap_factory::contract::instantiate,
+            terraswap_factory::contract::query,
+        )
+        .with_reply(terraswap_factory::contract::reply),
+    )
+}
+
+fn router_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_router::contract::execute,
+        terraswap_router::contract::instantiate,
+        terraswap_router::contract::query,
+    ))
+}
+
+fn dca_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_dca::contract::execute,
+        terraswap_dca::contract::instantiate,
+        terraswap_dca::contract::query,
+    ))
+}
+
+fn query_token_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    res.balance
+}
+
+fn instantiate_token(app: &mut App, code_id: u64, symbol: &str) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &TokenInstantiateMsg {
+            name: "terraswap token".to_string(),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: OWNER.to_string(),
+                amount: Uint128::from(1_000_000_000u128),
+            }],
+            mint: None,
+        },
+        &[],
+        "token",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn recurring_swaps_through_router() {
+    let mut app = App::default();
+
+    let token_code_id = app.store_code(token_contract());
+    let pair_code_id = app.store_code(pair_contract());
+    let factory_code_id = app.store_code(factory_contract());
+    let router_code_id = app.store_code(router_contract());
+    let dca_code_id = app.store_code(dca_contract());
+
+    let offer_token = instantiate_token(&mut app, token_code_id, "OFR");
+    let ask_token = instantiate_token(&mut app, token_code_id, "ASK");
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(OWNER),
+            &FactoryInstantiateMsg {
+                pair_code_id,
+                token_code_id,
+                timelock_delay: 0,
+            },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    let router = app
+        .instantiate_contract(
+            router_code_id,
+            Addr::unchecked(OWNER),
+            &RouterInstantiateMsg {
+                terraswap_factory: factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let offer_asset_info = AssetInfo::Token {
+        contract_addr: offer_token.to_string(),
+    };
+    let ask_asset_info = AssetInfo::Token {
+        contract_addr: ask_token.to_string(),
+    };
+
+    // create the pair with 1:1 initial liquidity
+    for token in [&offer_token, &ask_token] {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: factory.to_string(),
+                amount: Uint128::from(100_000_000u128),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory,
+        &FactoryExecuteMsg::CreatePair {
+            assets: [
+                Asset {
+                    info: offer_asset_info.clone(),
+                    amount: Uint128::from(100_000_000u128),
+                },
+                Asset {
+                    info: ask_asset_info.clone(),
+                    amount: Uint128::from(100_000_000u128),
+                },
+            ],
+            label: None,
+            fee_tier: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let dca = app
+        .instantiate_contract(
+            dca_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                terraswap_router: router.to_string(),
+            },
+            &[],
+            "dca",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        offer_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: USER.to_string(),
+            amount: Uint128::from(2_000_000u128),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // buy with 1_000_000 every hour
+    app.execute_contract(
+        Addr::unchecked(USER),
+        offer_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: dca.to_string(),
+            amount: Uint128::from(2_000_000u128),
+            msg: to_binary(&Cw20HookMsg::CreateSchedule {
+                interval: 3600,
+                amount_per_execution: Uint128::from(1_000_000u128),
+                operations: vec![SwapOperation::TerraSwap {
+                    offer_asset_info,
+                    ask_asset_info,
+                    fee_tier: None,
+                }],
+                minimum_receive: Uint128::from(900_000u128),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(KEEPER),
+        dca.clone(),
+        &ExecuteMsg::Trigger { id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // 987_128 = (100_000_000 * 1_000_000 / 101_000_000) * 0.997
+    assert_eq!(
+        query_token_balance(&app, &ask_token, USER),
+        Uint128::from(987_128u128)
+    );
+
+    // the interval has not elapsed
+    app.execute_contract(
+        Addr::unchecked(KEEPER),
+        dca.clone(),
+        &ExecuteMsg::Trigger { id: 1 },
+        &[],
+    )
+    .unwrap_err();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(3600);
+        block.height += 600;
+    });
+
+    let res: ScheduleResponse = app
+        .wrap()
+        .query_wasm_smart(&dca, &QueryMsg::Schedule { id: 1 })
+        .unwrap();
+    assert_eq!(res.offer_asset.amount, Uint128::from(1_000_000u128));
+
+    app.execute_contract(
+        Addr::unchecked(KEEPER),
+        dca.clone(),
+        &ExecuteMsg::Trigger { id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // 970_714 = 99_012_872 * 1_000_000 / 102_000_000, less 2_913 commission
+    assert_eq!(
+        query_token_balance(&app, &ask_token, USER),
+        Uint128::from(987_128u128 + 967_801u128)
+    );
+    assert_eq!(
+        query_token_balance(&app, &offer_token, dca.as_str()),
+        Uint128::zero()
+    );
+
+    // the finished schedule is removed
+    let res: Result<ScheduleResponse, _> = app
+        .wrap()
+        .query_wasm_smart(&dca, &QueryMsg::Schedule { id: 1 });
+    assert!(res.is_err());
+}
@@ -1,37 +1,48 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Reply,
-    ReplyOn, Response, StdError, StdResult, SubMsg, WasmMsg,
+    coin, to_binary, Addr, Binary, Coin, ContractInfoResponse, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, QueryRequest, Reply, ReplyOn, Response, StdError, StdResult,
+    SubMsg, SubMsgResult, Uint128, WasmMsg, WasmQuery,
 };
 use cw2::set_contract_version;
 use cw20::Cw20ExecuteMsg;
-use terraswap::querier::{query_balance, query_pair_info_from_pair};
+use cw_storage_plus::Bound;
+use terraswap::querier::{query_balance, query_pair_info_from_pair, query_pool};
 
 use crate::response::MsgInstantiateContractResponse;
 use crate::state::{
-    add_allow_native_token, pair_key, read_pairs, Config, TmpPairInfo, ALLOW_NATIVE_TOKENS, CONFIG,
-    PAIRS, TMP_PAIR_INFO,
+    add_allow_native_token, deregister_pair, find_unindexed_pair, index_pair, read_assets,
+    read_assets_metadata, read_creators, read_deregistered_pairs, read_pairs, read_pairs_by_asset,
+    read_pairs_metadata, store_pair, AssetMetadataRaw, Config, OwnershipProposal, PendingAction,
+    TmpPairInfo, ALLOWED_ASSETS, ALLOW_NATIVE_TOKENS, ASSET_METADATA, BLACKLISTED_ASSETS, CONFIG,
+    CREATORS, DEFAULT_LIMIT, INDEX_BACKFILL_CURSOR, LAST_ACTION_ID, LAST_REPLY_ID, MAX_LIMIT,
+    OWNERSHIP_PROPOSAL, PAIRS, PAIRS_BY_ADDRESS, PAIRS_BY_LIQUIDITY_TOKEN, PAIR_GENERATIONS,
+    PAIR_METADATA, PENDING_ACTIONS, TMP_PAIR_INFOS,
 };
 
 use protobuf::Message;
 use terraswap::asset::{Asset, AssetInfo, AssetInfoRaw, PairInfo, PairInfoRaw};
 use terraswap::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, NativeTokenDecimalsResponse,
-    PairsResponse, QueryMsg,
+    AllowedAssetsResponse, AssetMetadata, AssetsMetadataResponse, BlacklistedAssetsResponse,
+    ConfigResponse, CreatorsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    NativeTokenDecimalsResponse, OutdatedPairsResponse, PairAddressResponse, PairInfoMetadata,
+    PairMetadata, PairWithPool, PairsMetadataResponse, PairsResponse, PairsWithPoolsResponse,
+    PendingActionResponse, PendingActionsResponse, QueryMsg, TimelockAction,
 };
 use terraswap::pair::{
     ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg,
     MigrateMsg as PairMigrateMsg,
 };
-use terraswap::util::migrate_version;
+use terraswap::util::{
+    compute_initial_share, compute_pair_address, fee_tier_pair_key, migrate_version, pair_label,
+    pair_salt, DEFAULT_FEE_TIER, FEE_TIERS, MAX_LABEL_LENGTH, MINIMUM_LIQUIDITY_AMOUNT,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:terraswap-factory";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const CREATE_PAIR_REPLY_ID: u64 = 1;
-
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -45,6 +56,14 @@ pub fn instantiate(
         owner: deps.api.addr_canonicalize(info.sender.as_str())?,
         token_code_id: msg.token_code_id,
         pair_code_id: msg.pair_code_id,
+        max_referral_commission: Decimal::zero(),
+        timelock_delay: msg.timelock_delay,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: true,
+        restrict_assets: false,
+        guardian: None,
+        paused: false,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -56,29 +75,322 @@ pub fn instantiate(
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
         ExecuteMsg::UpdateConfig {
-            owner,
             token_code_id,
             pair_code_id,
-        } => execute_update_config(deps, env, info, owner, token_code_id, pair_code_id),
-        ExecuteMsg::CreatePair { assets } => execute_create_pair(deps, env, info, assets),
+            max_referral_commission,
+            timelock_delay,
+            creation_fee,
+            fee_recipient,
+            permissionless,
+            restrict_assets,
+            guardian,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            token_code_id,
+            pair_code_id,
+            max_referral_commission,
+            timelock_delay,
+            creation_fee,
+            fee_recipient,
+            permissionless,
+            restrict_assets,
+            guardian,
+        ),
+        ExecuteMsg::CreatePair {
+            assets,
+            fee_tier,
+            label,
+        } => execute_create_pair(deps, env, info, assets, fee_tier, label),
         ExecuteMsg::AddNativeTokenDecimals { denom, decimals } => {
             execute_add_native_token_decimals(deps, env, info, denom, decimals)
         }
+        ExecuteMsg::UpdateCreators { add, remove } => {
+            execute_update_creators(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateAllowedAssets { add, remove } => {
+            execute_update_allowed_assets(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateBlacklist { add, remove } => {
+            execute_update_blacklist(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdatePairMetadata {
+            asset_infos,
+            fee_tier,
+            metadata,
+        } => execute_update_pair_metadata(deps, info, asset_infos, fee_tier, metadata),
+        ExecuteMsg::UpdateAssetMetadata {
+            asset_info,
+            metadata,
+        } => execute_update_asset_metadata(deps, info, asset_info, metadata),
+        ExecuteMsg::Pause {} => execute_pause(deps, info),
+        ExecuteMsg::Unpause {} => execute_unpause(deps, info),
+        ExecuteMsg::DeregisterPair {
+            asset_infos,
+            fee_tier,
+        } => execute_deregister_pair(deps, info, asset_infos, fee_tier),
         ExecuteMsg::MigratePair { contract, code_id } => {
             execute_migrate_pair(deps, env, info, contract, code_id)
         }
+        ExecuteMsg::MigratePairs {
+            start_after,
+            limit,
+            code_id,
+        } => execute_migrate_pairs(deps, env, info, start_after, limit, code_id),
+        ExecuteMsg::BackfillIndexes { limit } => execute_backfill_indexes(deps, info, limit),
+        ExecuteMsg::ExecuteAction { id } => execute_action(deps, env, id),
+        ExecuteMsg::CancelAction { id } => execute_cancel_action(deps, info, id),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            execute_propose_new_owner(deps, env, info, owner, expires_in)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::DropOwnershipProposal {} => execute_drop_ownership_proposal(deps, info),
     }
 }
 
-// Only owner can execute it
+// Only owner can execute it, the update is queued until the timelock delay passes
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    owner: Option<String>,
     token_code_id: Option<u64>,
     pair_code_id: Option<u64>,
+    max_referral_commission: Option<Decimal>,
+    timelock_delay: Option<u64>,
+    creation_fee: Option<Asset>,
+    fee_recipient: Option<String>,
+    permissionless: Option<bool>,
+    restrict_assets: Option<bool>,
+    guardian: Option<String>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if let Some(max_referral_commission) = max_referral_commission {
+        if max_referral_commission > Decimal::one() {
+            return Err(StdError::generic_err(
+                "max_referral_commission must be less than or equal to 1",
+            ));
+        }
+    }
+
+    // validate address format
+    if let Some(creation_fee) = &creation_fee {
+        creation_fee.to_raw(deps.api)?;
+    }
+
+    if let Some(fee_recipient) = &fee_recipient {
+        deps.api.addr_validate(fee_recipient)?;
+    }
+
+    // an empty guardian removes it
+    if let Some(guardian) = &guardian {
+        if !guardian.is_empty() {
+            deps.api.addr_validate(guardian)?;
+        }
+    }
+
+    queue_action(
+        deps,
+        env,
+        &config,
+        TimelockAction::UpdateConfig {
+            token_code_id,
+            pair_code_id,
+            max_referral_commission,
+            timelock_delay,
+            creation_fee,
+            fee_recipient,
+            permissionless,
+            restrict_assets,
+            guardian,
+        },
+    )
+}
+
+fn queue_action(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    action: TimelockAction,
 ) -> StdResult<Response> {
+    let id = LAST_ACTION_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LAST_ACTION_ID.save(deps.storage, &id)?;
+
+    let eta = env.block.time.seconds() + config.timelock_delay;
+    PENDING_ACTIONS.save(deps.storage, id, &PendingAction { action, eta })?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "queue_action"),
+        ("id", &id.to_string()),
+        ("eta", &eta.to_string()),
+    ]))
+}
+
+// Anyone can execute it once the eta has passed
+pub fn execute_action(deps: DepsMut, env: Env, id: u64) -> StdResult<Response> {
+    let pending_action = PENDING_ACTIONS
+        .may_load(deps.storage, id)?
+        .ok_or_else(|| StdError::generic_err("pending action not found"))?;
+
+    if env.block.time.seconds() < pending_action.eta {
+        return Err(StdError::generic_err(format!(
+            "pending action can be executed from {}",
+            pending_action.eta
+        )));
+    }
+
+    PENDING_ACTIONS.remove(deps.storage, id);
+
+    let res = match pending_action.action {
+        TimelockAction::UpdateConfig {
+            token_code_id,
+            pair_code_id,
+            max_referral_commission,
+            timelock_delay,
+            creation_fee,
+            fee_recipient,
+            permissionless,
+            restrict_assets,
+            guardian,
+        } => {
+            let mut config: Config = CONFIG.load(deps.storage)?;
+
+            if let Some(token_code_id) = token_code_id {
+                config.token_code_id = token_code_id;
+            }
+
+            if let Some(pair_code_id) = pair_code_id {
+                config.pair_code_id = pair_code_id;
+            }
+
+            if let Some(max_referral_commission) = max_referral_commission {
+                config.max_referral_commission = max_referral_commission;
+            }
+
+            if let Some(timelock_delay) = timelock_delay {
+                config.timelock_delay = timelock_delay;
+            }
+
+            if let Some(creation_fee) = creation_fee {
+                config.creation_fee = if creation_fee.amount.is_zero() {
+                    None
+                } else {
+                    Some(creation_fee.to_raw(deps.api)?)
+                };
+            }
+
+            if let Some(fee_recipient) = fee_recipient {
+                config.fee_recipient = Some(deps.api.addr_canonicalize(&fee_recipient)?);
+            }
+
+            if let Some(permissionless) = permissionless {
+                config.permissionless = permissionless;
+            }
+
+            if let Some(restrict_assets) = restrict_assets {
+                config.restrict_assets = restrict_assets;
+            }
+
+            if let Some(guardian) = guardian {
+                config.guardian = if guardian.is_empty() {
+                    None
+                } else {
+                    Some(deps.api.addr_canonicalize(&guardian)?)
+                };
+            }
+
+            CONFIG.save(deps.storage, &config)?;
+
+            Response::new().add_attribute("action", "update_config")
+        }
+        TimelockAction::MigratePair { contract, code_id } => Response::new()
+            .add_attribute("action", "migrate_pair")
+            .add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: contract,
+                new_code_id: code_id,
+                msg: to_binary(&PairMigrateMsg {})?,
+            })),
+        TimelockAction::MigratePairs {
+            start_after,
+            limit,
+            code_id,
+        } => {
+            let start_after = if let Some(start_after) = start_after {
+                Some([
+                    start_after[0].to_raw(deps.api)?,
+                    start_after[1].to_raw(deps.api)?,
+                ])
+            } else {
+                None
+            };
+
+            let pairs = read_pairs(deps.storage, deps.api, start_after, limit)?;
+            let messages = pairs
+                .iter()
+                .map(|pair| {
+                    Ok(CosmosMsg::Wasm(WasmMsg::Migrate {
+                        contract_addr: pair.contract_addr.to_string(),
+                        new_code_id: code_id,
+                        msg: to_binary(&PairMigrateMsg {})?,
+                    }))
+                })
+                .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+            Response::new()
+                .add_attributes(vec![
+                    ("action", "migrate_pairs"),
+                    ("count", &pairs.len().to_string()),
+                ])
+                .add_messages(messages)
+        }
+    };
+
+    Ok(res.add_attribute("id", id.to_string()))
+}
+
+// Only owner can execute it
+pub fn execute_cancel_action(deps: DepsMut, info: MessageInfo, id: u64) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if !PENDING_ACTIONS.has(deps.storage, id) {
+        return Err(StdError::generic_err("pending action not found"));
+    }
+
+    PENDING_ACTIONS.remove(deps.storage, id);
+
+    Ok(Response::new().add_attributes(vec![("action", "cancel_action"), ("id", &id.to_string())]))
+}
+
+// The owner or the guardian can execute it
+pub fn execute_pause(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender != config.owner && Some(sender) != config.guardian {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.paused = true;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "pause"))
+}
+
+// Only owner can execute it
+pub fn execute_unpause(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
@@ -86,39 +398,131 @@ pub fn execute_update_config(
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    if let Some(owner) = owner {
-        // validate address format
-        let _ = deps.api.addr_validate(&owner)?;
+    config.paused = false;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
+// Only owner can execute it
+pub fn execute_propose_new_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    expires_in: u64,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
 
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+    let new_owner = deps
+        .api
+        .addr_canonicalize(deps.api.addr_validate(&owner)?.as_str())?;
+    if new_owner == config.owner {
+        return Err(StdError::generic_err(
+            "new owner cannot be the current owner",
+        ));
     }
 
-    if let Some(token_code_id) = token_code_id {
-        config.token_code_id = token_code_id;
+    let expires_at = env.block.time.seconds() + expires_in;
+    OWNERSHIP_PROPOSAL.save(
+        deps.storage,
+        &OwnershipProposal {
+            owner: new_owner,
+            expires_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "propose_new_owner"),
+        ("owner", &owner),
+        ("expires_at", &expires_at.to_string()),
+    ]))
+}
+
+// Only the proposed owner can execute it
+pub fn execute_accept_ownership(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let proposal = OWNERSHIP_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("ownership proposal not found"))?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != proposal.owner {
+        return Err(StdError::generic_err("unauthorized"));
     }
 
-    if let Some(pair_code_id) = pair_code_id {
-        config.pair_code_id = pair_code_id;
+    if env.block.time.seconds() >= proposal.expires_at {
+        return Err(StdError::generic_err("ownership proposal expired"));
     }
 
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    config.owner = proposal.owner;
     CONFIG.save(deps.storage, &config)?;
+    OWNERSHIP_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "accept_ownership"),
+        ("owner", info.sender.as_str()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_drop_ownership_proposal(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if OWNERSHIP_PROPOSAL.may_load(deps.storage)?.is_none() {
+        return Err(StdError::generic_err("ownership proposal not found"));
+    }
+
+    OWNERSHIP_PROPOSAL.remove(deps.storage);
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
 }
 
-// Anyone can execute it to create swap pair
+// Anyone can execute it to create swap pair, unless the creation is permissioned
 pub fn execute_create_pair(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     assets: [Asset; 2],
+    fee_tier: Option<u16>,
+    label: Option<String>,
 ) -> StdResult<Response> {
     let config: Config = CONFIG.load(deps.storage)?;
 
+    if config.paused {
+        return Err(StdError::generic_err("factory is paused"));
+    }
+
+    // permission check
+    if !config.permissionless {
+        let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+        if sender != config.owner && !CREATORS.has(deps.storage, sender.as_slice()) {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+    }
+
     if assets[0].info == assets[1].info {
         return Err(StdError::generic_err("same asset"));
     }
 
+    let fee_tier = fee_tier.unwrap_or(DEFAULT_FEE_TIER);
+    if !FEE_TIERS.contains(&fee_tier) {
+        return Err(StdError::generic_err(format!(
+            "fee tier {} is not supported",
+            fee_tier
+        )));
+    }
+
     let asset_1_decimal = match assets[0]
         .info
         .query_decimals(env.contract.address.clone(), &deps.querier)
@@ -143,20 +547,127 @@ pub fn execute_create_pair(
         asset_infos[1].to_raw(deps.api)?,
     ];
 
+    for (asset_info, raw_info) in asset_infos.iter().zip(raw_infos.iter()) {
+        if BLACKLISTED_ASSETS.has(deps.storage, raw_info.as_bytes()) {
+            return Err(StdError::generic_err(format!(
+                "{} is blacklisted",
+                asset_info
+            )));
+        }
+    }
+
+    if config.restrict_assets {
+        for (asset_info, raw_info) in asset_infos.iter().zip(raw_infos.iter()) {
+            if !ALLOWED_ASSETS.has(deps.storage, raw_info.as_bytes()) {
+                return Err(StdError::generic_err(format!(
+                    "{} is not allowed",
+                    asset_info
+                )));
+            }
+        }
+    }
+
     let asset_decimals = [asset_1_decimal, asset_2_decimal];
 
-    let pair_key = pair_key(&raw_infos);
+    // the initial provision runs in the reply, validate it up front
+    // so that a bad deposit does not revert the creation with a pair error
+    if !assets[0].amount.is_zero() || !assets[1].amount.is_zero() {
+        assert_initial_deposit(&info, &assets)?;
+    }
+
+    let label = match label {
+        Some(label) => {
+            if label.is_empty() {
+                return Err(StdError::generic_err("label cannot be empty"));
+            }
+
+            if label.len() > MAX_LABEL_LENGTH {
+                return Err(StdError::generic_err(format!(
+                    "label cannot be longer than {} bytes",
+                    MAX_LABEL_LENGTH
+                )));
+            }
+
+            label
+        }
+        None => pair_label(
+            "terraswap pair",
+            &[
+                query_label_symbol(deps.as_ref(), &asset_infos[0], &raw_infos[0]),
+                query_label_symbol(deps.as_ref(), &asset_infos[1], &raw_infos[1]),
+            ],
+        ),
+    };
+
+    let pair_key = fee_tier_pair_key(&raw_infos, fee_tier);
     if let Ok(Some(_)) = PAIRS.may_load(deps.storage, &pair_key) {
         return Err(StdError::generic_err("Pair already exists"));
     }
 
-    TMP_PAIR_INFO.save(
+    // the fee is held by the factory until the reply
+    let creation_fee = config
+        .creation_fee
+        .as_ref()
+        .map(|creation_fee| creation_fee.to_normal(deps.api))
+        .transpose()?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Some(creation_fee) = &creation_fee {
+        match &creation_fee.info {
+            AssetInfo::NativeToken { denom } => {
+                let deposit: Uint128 = assets
+                    .iter()
+                    .filter(|asset| asset.info == creation_fee.info)
+                    .map(|asset| asset.amount)
+                    .sum();
+                let sent = info
+                    .funds
+                    .iter()
+                    .find(|coin| coin.denom == *denom)
+                    .map(|coin| coin.amount)
+                    .unwrap_or_default();
+
+                if sent < deposit + creation_fee.amount {
+                    return Err(StdError::generic_err(format!(
+                        "creation fee of {} is required",
+                        creation_fee
+                    )));
+                }
+            }
+            AssetInfo::Token { contract_addr } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: creation_fee.amount,
+                    })?,
+                    funds: vec![],
+                }));
+            }
+        }
+    }
+
+    assert_no_excess_funds(&info, &assets, creation_fee.as_ref())?;
+
+    // each creation gets its own reply id, so several pairs can be
+    // created within a single transaction
+    let reply_id = LAST_REPLY_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LAST_REPLY_ID.save(deps.storage, &reply_id)?;
+
+    let generation = PAIR_GENERATIONS
+        .may_load(deps.storage, &pair_key)?
+        .unwrap_or_default();
+    let salt = pair_salt(&pair_key, generation);
+    TMP_PAIR_INFOS.save(
         deps.storage,
+        reply_id,
         &TmpPairInfo {
             pair_key,
             assets: raw_assets,
             asset_decimals,
             sender: info.sender,
+            creation_fee: config.creation_fee,
+            fee_tier,
         },
     )?;
 
@@ -164,25 +675,118 @@ pub fn execute_create_pair(
         .add_attributes(vec![
             ("action", "create_pair"),
             ("pair", &format!("{}-{}", assets[0].info, assets[1].info)),
+            ("fee_tier", &fee_tier.to_string()),
         ])
+        .add_messages(messages)
         .add_submessage(SubMsg {
-            id: CREATE_PAIR_REPLY_ID,
+            id: reply_id,
             gas_limit: None,
-            msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
+            msg: CosmosMsg::Wasm(WasmMsg::Instantiate2 {
                 code_id: config.pair_code_id,
                 funds: vec![],
                 admin: Some(env.contract.address.to_string()),
-                label: "pair".to_string(),
+                label,
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos,
                     token_code_id: config.token_code_id,
                     asset_decimals,
+                    fee_tier: Some(fee_tier),
                 })?,
+                salt,
             }),
-            reply_on: ReplyOn::Success,
+            reply_on: ReplyOn::Always,
         }))
 }
 
+// Both assets are deposited, the native ones are sent along,
+// and the share covers MINIMUM_LIQUIDITY_AMOUNT like the pair requires
+fn assert_initial_deposit(info: &MessageInfo, assets: &[Asset; 2]) -> StdResult<()> {
+    if assets[0].amount.is_zero() || assets[1].amount.is_zero() {
+        return Err(StdError::generic_err(
+            "initial liquidity requires both assets",
+        ));
+    }
+
+    for asset in assets.iter() {
+        if let AssetInfo::NativeToken { denom } = &asset.info {
+            let sent = info
+                .funds
+                .iter()
+                .find(|coin| coin.denom == *denom)
+                .map(|coin| coin.amount)
+                .unwrap_or_default();
+            if sent < asset.amount {
+                return Err(StdError::generic_err(format!(
+                    "initial deposit of {} must be sent",
+                    asset
+                )));
+            }
+        }
+    }
+
+    let share = compute_initial_share([assets[0].amount, assets[1].amount])
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    if share <= Uint128::from(MINIMUM_LIQUIDITY_AMOUNT) {
+        return Err(StdError::generic_err(format!(
+            "initial liquidity must mint more than {} LP tokens, {} given",
+            MINIMUM_LIQUIDITY_AMOUNT, share
+        )));
+    }
+
+    Ok(())
+}
+
+// Only the native deposits and the native creation fee can be sent,
+// the excess would be kept by the factory
+fn assert_no_excess_funds(
+    info: &MessageInfo,
+    assets: &[Asset; 2],
+    creation_fee: Option<&Asset>,
+) -> StdResult<()> {
+    for coin in info.funds.iter() {
+        let coin_info = AssetInfo::NativeToken {
+            denom: coin.denom.clone(),
+        };
+        let deposit: Uint128 = assets
+            .iter()
+            .filter(|asset| asset.info == coin_info)
+            .map(|asset| asset.amount)
+            .sum();
+
+        match creation_fee.filter(|creation_fee| creation_fee.info == coin_info) {
+            Some(creation_fee) => {
+                if coin.amount > deposit + creation_fee.amount {
+                    return Err(StdError::generic_err(format!(
+                        "sent {} exceeds the deposit and the creation fee of {}",
+                        coin, creation_fee
+                    )));
+                }
+            }
+            None => {
+                if coin.amount > deposit {
+                    return Err(StdError::generic_err(format!(
+                        "sent {} exceeds the deposit of {}{}",
+                        coin, deposit, coin.denom
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the registry symbol when set, the denom or the cw20 symbol otherwise
+fn query_label_symbol(deps: Deps, asset_info: &AssetInfo, raw_info: &AssetInfoRaw) -> String {
+    if let Ok(Some(asset_metadata)) = ASSET_METADATA.may_load(deps.storage, raw_info.as_bytes()) {
+        return asset_metadata.metadata.symbol;
+    }
+
+    asset_info
+        .query_symbol(&deps.querier)
+        .unwrap_or_else(|_| asset_info.to_string())
+}
+
 pub fn execute_add_native_token_decimals(
     deps: DepsMut,
     env: Env,
@@ -213,12 +817,12 @@ pub fn execute_add_native_token_decimals(
     ]))
 }
 
-pub fn execute_migrate_pair(
+// Only owner can execute it
+pub fn execute_update_creators(
     deps: DepsMut,
-    _env: Env,
     info: MessageInfo,
-    contract: String,
-    code_id: Option<u64>,
+    add: Vec<String>,
+    remove: Vec<String>,
 ) -> StdResult<Response> {
     let config: Config = CONFIG.load(deps.storage)?;
 
@@ -227,47 +831,340 @@ pub fn execute_migrate_pair(
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let code_id = code_id.unwrap_or(config.pair_code_id);
+    for creator in add.iter() {
+        let creator = deps.api.addr_canonicalize(creator)?;
+        CREATORS.save(deps.storage, creator.as_slice(), &Empty {})?;
+    }
 
-    Ok(
-        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
-            contract_addr: contract,
-            new_code_id: code_id,
-            msg: to_binary(&PairMigrateMsg {})?,
-        })),
-    )
+    for creator in remove.iter() {
+        let creator = deps.api.addr_canonicalize(creator)?;
+        CREATORS.remove(deps.storage, creator.as_slice());
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_creators"),
+        ("added", &add.len().to_string()),
+        ("removed", &remove.len().to_string()),
+    ]))
 }
 
-/// This just stores the result for future query
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
-    if msg.id != CREATE_PAIR_REPLY_ID {
-        return Err(StdError::generic_err("invalid reply msg"));
+// Only owner can execute it
+pub fn execute_update_allowed_assets(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<AssetInfo>,
+    remove: Vec<AssetInfo>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
     }
 
-    let tmp_pair_info = TMP_PAIR_INFO.load(deps.storage)?;
+    for asset_info in add.iter() {
+        let raw_info = asset_info.to_raw(deps.api)?;
+        ALLOWED_ASSETS.save(deps.storage, raw_info.as_bytes(), &raw_info)?;
+    }
 
-    let res: MsgInstantiateContractResponse =
-        Message::parse_from_bytes(msg.result.unwrap().data.unwrap().as_slice()).map_err(|_| {
-            StdError::parse_err("MsgInstantiateContractResponse", "failed to parse data")
-        })?;
+    for asset_info in remove.iter() {
+        let raw_info = asset_info.to_raw(deps.api)?;
+        ALLOWED_ASSETS.remove(deps.storage, raw_info.as_bytes());
+    }
 
-    let pair_contract = res.get_address();
-    let pair_info = query_pair_info_from_pair(&deps.querier, Addr::unchecked(pair_contract))?;
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_allowed_assets"),
+        ("added", &add.len().to_string()),
+        ("removed", &remove.len().to_string()),
+    ]))
+}
 
-    let raw_infos = [
-        tmp_pair_info.assets[0].info.clone(),
-        tmp_pair_info.assets[1].info.clone(),
-    ];
+// Only owner can execute it
+pub fn execute_update_blacklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<AssetInfo>,
+    remove: Vec<AssetInfo>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
 
-    PAIRS.save(
-        deps.storage,
-        &tmp_pair_info.pair_key,
-        &PairInfoRaw {
-            liquidity_token: deps.api.addr_canonicalize(&pair_info.liquidity_token)?,
-            contract_addr: deps.api.addr_canonicalize(pair_contract)?,
-            asset_infos: raw_infos,
-            asset_decimals: tmp_pair_info.asset_decimals,
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    for asset_info in add.iter() {
+        let raw_info = asset_info.to_raw(deps.api)?;
+        BLACKLISTED_ASSETS.save(deps.storage, raw_info.as_bytes(), &raw_info)?;
+    }
+
+    for asset_info in remove.iter() {
+        let raw_info = asset_info.to_raw(deps.api)?;
+        BLACKLISTED_ASSETS.remove(deps.storage, raw_info.as_bytes());
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_blacklist"),
+        ("added", &add.len().to_string()),
+        ("removed", &remove.len().to_string()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_update_pair_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    fee_tier: Option<u16>,
+    metadata: Option<PairMetadata>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair_key = fee_tier_pair_key(
+        &[
+            asset_infos[0].to_raw(deps.api)?,
+            asset_infos[1].to_raw(deps.api)?,
+        ],
+        fee_tier.unwrap_or(DEFAULT_FEE_TIER),
+    );
+    if !PAIRS.has(deps.storage, &pair_key) {
+        return Err(StdError::generic_err("pair not found"));
+    }
+
+    match metadata {
+        Some(metadata) => PAIR_METADATA.save(deps.storage, &pair_key, &metadata)?,
+        None => PAIR_METADATA.remove(deps.storage, &pair_key),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_pair_metadata"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_update_asset_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    metadata: Option<AssetMetadata>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let raw_info = asset_info.to_raw(deps.api)?;
+    match metadata {
+        Some(metadata) => {
+            if !asset_info.is_native_token() && metadata.decimals.is_some() {
+                return Err(StdError::generic_err(
+                    "decimals can only be set for native tokens",
+                ));
+            }
+
+            ASSET_METADATA.save(
+                deps.storage,
+                raw_info.as_bytes(),
+                &AssetMetadataRaw {
+                    asset_info: raw_info.clone(),
+                    metadata,
+                },
+            )?
+        }
+        None => ASSET_METADATA.remove(deps.storage, raw_info.as_bytes()),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_asset_metadata"),
+        ("asset", &asset_info.to_string()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_deregister_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    fee_tier: Option<u16>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let pair_key = fee_tier_pair_key(
+        &[
+            asset_infos[0].to_raw(deps.api)?,
+            asset_infos[1].to_raw(deps.api)?,
+        ],
+        fee_tier.unwrap_or(DEFAULT_FEE_TIER),
+    );
+    let pair_info = PAIRS
+        .may_load(deps.storage, &pair_key)?
+        .ok_or_else(|| StdError::generic_err("pair not found"))?;
+
+    deregister_pair(deps.storage, &pair_key, &pair_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deregister_pair"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+        (
+            "pair_contract_addr",
+            deps.api.addr_humanize(&pair_info.contract_addr)?.as_str(),
+        ),
+    ]))
+}
+
+// Only owner can execute it, the migration is queued until the timelock delay passes
+pub fn execute_migrate_pair(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    code_id: Option<u64>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // validate address format
+    let _ = deps.api.addr_validate(&contract)?;
+    let code_id = code_id.unwrap_or(config.pair_code_id);
+
+    queue_action(
+        deps,
+        env,
+        &config,
+        TimelockAction::MigratePair { contract, code_id },
+    )
+}
+
+// Only owner can execute it, the migration is queued until the timelock delay passes
+pub fn execute_migrate_pairs(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+    code_id: Option<u64>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let code_id = code_id.unwrap_or(config.pair_code_id);
+
+    queue_action(
+        deps,
+        env,
+        &config,
+        TimelockAction::MigratePairs {
+            start_after,
+            limit,
+            code_id,
+        },
+    )
+}
+
+// Only owner can execute it, the pairs are indexed in the order of the `pairs` query
+pub fn execute_backfill_indexes(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let cursor = INDEX_BACKFILL_CURSOR
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("pair indexes are already backfilled"))?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = if cursor.is_empty() {
+        None
+    } else {
+        Some(Bound::ExclusiveRaw(cursor))
+    };
+
+    let pairs = PAIRS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(Vec<u8>, PairInfoRaw)>>>()?;
+    for (pair_key, pair_info) in pairs.iter() {
+        index_pair(deps.storage, pair_key, pair_info)?;
+    }
+
+    let done = pairs.len() < limit;
+    if done {
+        INDEX_BACKFILL_CURSOR.remove(deps.storage);
+    } else if let Some((pair_key, _)) = pairs.last() {
+        INDEX_BACKFILL_CURSOR.save(deps.storage, pair_key)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "backfill_indexes"),
+        ("indexed_pairs", &pairs.len().to_string()),
+        ("done", &done.to_string()),
+    ]))
+}
+
+/// This stores the result for future query, or refunds the sender
+/// when the pair instantiation failed
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let tmp_pair_info = TMP_PAIR_INFOS
+        .may_load(deps.storage, msg.id)?
+        .ok_or_else(|| StdError::generic_err("invalid reply msg"))?;
+    TMP_PAIR_INFOS.remove(deps.storage, msg.id);
+
+    let data = match msg.result {
+        SubMsgResult::Ok(res) => res.data,
+        SubMsgResult::Err(err) => return refund_create_pair(deps.as_ref(), tmp_pair_info, err),
+    };
+
+    let res: MsgInstantiateContractResponse = Message::parse_from_bytes(
+        data.ok_or_else(|| StdError::generic_err("missing instantiate data"))?
+            .as_slice(),
+    )
+    .map_err(|_| StdError::parse_err("MsgInstantiateContractResponse", "failed to parse data"))?;
+
+    let pair_contract = res.get_address();
+    let pair_info = query_pair_info_from_pair(&deps.querier, Addr::unchecked(pair_contract))?;
+
+    let raw_infos = [
+        tmp_pair_info.assets[0].info.clone(),
+        tmp_pair_info.assets[1].info.clone(),
+    ];
+
+    store_pair(
+        deps.storage,
+        &tmp_pair_info.pair_key,
+        &PairInfoRaw {
+            liquidity_token: deps.api.addr_canonicalize(&pair_info.liquidity_token)?,
+            contract_addr: deps.api.addr_canonicalize(pair_contract)?,
+            asset_infos: raw_infos,
+            asset_decimals: tmp_pair_info.asset_decimals,
+            fee_tier: tmp_pair_info.fee_tier,
         },
     )?;
 
@@ -317,6 +1214,16 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
         }));
     }
 
+    if let Some(creation_fee) = tmp_pair_info.creation_fee {
+        let config: Config = CONFIG.load(deps.storage)?;
+        let fee_recipient = config.fee_recipient.unwrap_or(config.owner);
+        messages.push(
+            creation_fee
+                .to_normal(deps.api)?
+                .into_msg(deps.api.addr_humanize(&fee_recipient)?)?,
+        );
+    }
+
     Ok(Response::new()
         .add_attributes(vec![
             ("pair_contract_addr", pair_contract),
@@ -325,44 +1232,180 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
         .add_messages(messages))
 }
 
+// Returns the creation fee and the native deposit, the cw20 deposit is only
+// pulled after a successful instantiation
+fn refund_create_pair(deps: Deps, tmp_pair_info: TmpPairInfo, err: String) -> StdResult<Response> {
+    let mut refunds: Vec<Asset> = vec![];
+    for asset in tmp_pair_info.assets.iter() {
+        if let AssetInfoRaw::NativeToken { .. } = &asset.info {
+            if !asset.amount.is_zero() {
+                refunds.push(asset.to_normal(deps.api)?);
+            }
+        }
+    }
+
+    if let Some(creation_fee) = &tmp_pair_info.creation_fee {
+        refunds.push(creation_fee.to_normal(deps.api)?);
+    }
+
+    let messages = refunds
+        .into_iter()
+        .map(|asset| asset.into_msg(tmp_pair_info.sender.clone()))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "refund_create_pair"),
+            ("sender", tmp_pair_info.sender.as_str()),
+            ("error", &err),
+        ])
+        .add_messages(messages))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::Pair { asset_infos } => to_binary(&query_pair(deps, asset_infos)?),
-        QueryMsg::Pairs { start_after, limit } => {
-            to_binary(&query_pairs(deps, start_after, limit)?)
+        QueryMsg::Pair {
+            asset_infos,
+            fee_tier,
+        } => to_binary(&query_pair(deps, asset_infos, fee_tier)?),
+        QueryMsg::PairsForAssets { asset_infos } => {
+            to_binary(&query_pairs_for_assets(deps, asset_infos)?)
         }
+        QueryMsg::Pairs {
+            start_after,
+            limit,
+            with_metadata,
+        } => to_binary(&query_pairs(deps, start_after, limit, with_metadata)?),
         QueryMsg::NativeTokenDecimals { denom } => {
             to_binary(&query_native_token_decimal(deps, denom)?)
         }
+        QueryMsg::PendingActions { start_after, limit } => {
+            to_binary(&query_pending_actions(deps, start_after, limit)?)
+        }
+        QueryMsg::PairByAddress { contract_addr } => {
+            to_binary(&query_pair_by_address(deps, contract_addr)?)
+        }
+        QueryMsg::PairByLiquidityToken { liquidity_token } => {
+            to_binary(&query_pair_by_liquidity_token(deps, liquidity_token)?)
+        }
+        QueryMsg::PairsByAsset {
+            asset_info,
+            start_after,
+            limit,
+        } => to_binary(&query_pairs_by_asset(deps, asset_info, start_after, limit)?),
+        QueryMsg::OutdatedPairs { start_after, limit } => {
+            to_binary(&query_outdated_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::Creators { start_after, limit } => {
+            to_binary(&query_creators(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowedAssets { start_after, limit } => {
+            to_binary(&query_allowed_assets(deps, start_after, limit)?)
+        }
+        QueryMsg::BlacklistedAssets { start_after, limit } => {
+            to_binary(&query_blacklisted_assets(deps, start_after, limit)?)
+        }
+        QueryMsg::DeregisteredPairs { start_after, limit } => {
+            to_binary(&query_deregistered_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::PairsWithPools { start_after, limit } => {
+            to_binary(&query_pairs_with_pools(deps, start_after, limit)?)
+        }
+        QueryMsg::PairsMetadata { start_after, limit } => {
+            to_binary(&query_pairs_metadata(deps, start_after, limit)?)
+        }
+        QueryMsg::AssetsMetadata { start_after, limit } => {
+            to_binary(&query_assets_metadata(deps, start_after, limit)?)
+        }
+        QueryMsg::PredictPairAddress {
+            asset_infos,
+            fee_tier,
+        } => to_binary(&query_predict_pair_address(
+            deps,
+            env,
+            asset_infos,
+            fee_tier,
+        )?),
     }
 }
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state: Config = CONFIG.load(deps.storage)?;
+    let pending_owner = match OWNERSHIP_PROPOSAL.may_load(deps.storage)? {
+        Some(proposal) => Some(deps.api.addr_humanize(&proposal.owner)?.to_string()),
+        None => None,
+    };
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        pending_owner,
         token_code_id: state.token_code_id,
         pair_code_id: state.pair_code_id,
+        max_referral_commission: state.max_referral_commission,
+        timelock_delay: state.timelock_delay,
+        creation_fee: match state.creation_fee {
+            Some(creation_fee) => Some(creation_fee.to_normal(deps.api)?),
+            None => None,
+        },
+        fee_recipient: match state.fee_recipient {
+            Some(fee_recipient) => Some(deps.api.addr_humanize(&fee_recipient)?.to_string()),
+            None => None,
+        },
+        permissionless: state.permissionless,
+        restrict_assets: state.restrict_assets,
+        guardian: match state.guardian {
+            Some(guardian) => Some(deps.api.addr_humanize(&guardian)?.to_string()),
+            None => None,
+        },
+        paused: state.paused,
     };
 
     Ok(resp)
 }
 
-pub fn query_pair(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<PairInfo> {
-    let pair_key = pair_key(&[
-        asset_infos[0].to_raw(deps.api)?,
-        asset_infos[1].to_raw(deps.api)?,
-    ]);
+pub fn query_pair(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    fee_tier: Option<u16>,
+) -> StdResult<PairInfo> {
+    let pair_key = fee_tier_pair_key(
+        &[
+            asset_infos[0].to_raw(deps.api)?,
+            asset_infos[1].to_raw(deps.api)?,
+        ],
+        fee_tier.unwrap_or(DEFAULT_FEE_TIER),
+    );
     let pair_info: PairInfoRaw = PAIRS.load(deps.storage, &pair_key)?;
     pair_info.to_normal(deps.api)
 }
 
+pub fn query_pairs_for_assets(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<PairsResponse> {
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ];
+
+    let mut pairs: Vec<PairInfo> = vec![];
+    for fee_tier in FEE_TIERS.iter() {
+        if let Some(pair_info) =
+            PAIRS.may_load(deps.storage, &fee_tier_pair_key(&raw_infos, *fee_tier))?
+        {
+            pairs.push(pair_info.to_normal(deps.api)?);
+        }
+    }
+
+    Ok(PairsResponse {
+        pairs,
+        metadata: None,
+    })
+}
+
 pub fn query_pairs(
     deps: Deps,
     start_after: Option<[AssetInfo; 2]>,
     limit: Option<u32>,
+    with_metadata: Option<bool>,
 ) -> StdResult<PairsResponse> {
     let start_after = if let Some(start_after) = start_after {
         Some([
@@ -374,11 +1417,295 @@ pub fn query_pairs(
     };
 
     let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?;
-    let resp = PairsResponse { pairs };
+    let metadata = if with_metadata.unwrap_or(false) {
+        Some(
+            pairs
+                .iter()
+                .map(|pair| query_pair_info_metadata(deps, pair))
+                .collect::<StdResult<Vec<PairInfoMetadata>>>()?,
+        )
+    } else {
+        None
+    };
+
+    let resp = PairsResponse { pairs, metadata };
 
     Ok(resp)
 }
 
+fn query_pair_info_metadata(deps: Deps, pair: &PairInfo) -> StdResult<PairInfoMetadata> {
+    let asset_metadata = |raw_info: &AssetInfoRaw| -> StdResult<Option<AssetMetadata>> {
+        Ok(ASSET_METADATA
+            .may_load(deps.storage, raw_info.as_bytes())?
+            .map(|asset_metadata| asset_metadata.metadata))
+    };
+
+    let raw_infos = [
+        pair.asset_infos[0].to_raw(deps.api)?,
+        pair.asset_infos[1].to_raw(deps.api)?,
+    ];
+
+    Ok(PairInfoMetadata {
+        metadata: PAIR_METADATA
+            .may_load(deps.storage, &fee_tier_pair_key(&raw_infos, pair.fee_tier))?,
+        asset_metadata: [
+            asset_metadata(&raw_infos[0])?,
+            asset_metadata(&raw_infos[1])?,
+        ],
+    })
+}
+
+pub fn query_pairs_with_pools(
+    deps: Deps,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsWithPoolsResponse> {
+    let pairs = query_pairs(deps, start_after, limit, None)?
+        .pairs
+        .into_iter()
+        .map(|pair| {
+            let pool = query_pool(&deps.querier, Addr::unchecked(&pair.contract_addr))?;
+
+            Ok(PairWithPool {
+                pair,
+                assets: pool.assets,
+                total_share: pool.total_share,
+            })
+        })
+        .collect::<StdResult<Vec<PairWithPool>>>()?;
+
+    Ok(PairsWithPoolsResponse { pairs })
+}
+
+pub fn query_pairs_metadata(
+    deps: Deps,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsMetadataResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let pairs = read_pairs_metadata(deps.storage, deps.api, start_after, limit)?;
+
+    Ok(PairsMetadataResponse { pairs })
+}
+
+pub fn query_assets_metadata(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<AssetsMetadataResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(start_after.to_raw(deps.api)?),
+        None => None,
+    };
+
+    let assets = read_assets_metadata(deps.storage, deps.api, start_after, limit)?;
+
+    Ok(AssetsMetadataResponse { assets })
+}
+
+pub fn query_pair_by_address(deps: Deps, contract_addr: String) -> StdResult<PairInfo> {
+    let contract_addr = deps.api.addr_canonicalize(&contract_addr)?;
+    let pair_info: PairInfoRaw =
+        match PAIRS_BY_ADDRESS.may_load(deps.storage, contract_addr.as_slice())? {
+            Some(pair_key) => PAIRS.load(deps.storage, &pair_key)?,
+            None => find_unindexed_pair(deps.storage, |pair_info| {
+                pair_info.contract_addr == contract_addr
+            })?,
+        };
+    pair_info.to_normal(deps.api)
+}
+
+pub fn query_pair_by_liquidity_token(deps: Deps, liquidity_token: String) -> StdResult<PairInfo> {
+    let liquidity_token = deps.api.addr_canonicalize(&liquidity_token)?;
+    let pair_info: PairInfoRaw =
+        match PAIRS_BY_LIQUIDITY_TOKEN.may_load(deps.storage, liquidity_token.as_slice())? {
+            Some(pair_key) => PAIRS.load(deps.storage, &pair_key)?,
+            None => find_unindexed_pair(deps.storage, |pair_info| {
+                pair_info.liquidity_token == liquidity_token
+            })?,
+        };
+    pair_info.to_normal(deps.api)
+}
+
+pub fn query_pairs_by_asset(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    if INDEX_BACKFILL_CURSOR.may_load(deps.storage)?.is_some() {
+        return Err(StdError::generic_err("pair indexes are not backfilled yet"));
+    }
+
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let pairs: Vec<PairInfo> = read_pairs_by_asset(
+        deps.storage,
+        deps.api,
+        &asset_info.to_raw(deps.api)?,
+        start_after,
+        limit,
+    )?;
+
+    Ok(PairsResponse {
+        pairs,
+        metadata: None,
+    })
+}
+
+pub fn query_outdated_pairs(
+    deps: Deps,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<OutdatedPairsResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    // the scan is bounded by the page like MigratePairs,
+    // every scanned pair costs a contract info query
+    let scanned_pairs = read_pairs(deps.storage, deps.api, start_after, limit)?;
+    let last_scanned = scanned_pairs.last().map(|pair| pair.asset_infos.clone());
+
+    let mut pairs: Vec<PairInfo> = vec![];
+    for pair in scanned_pairs {
+        let contract_info: ContractInfoResponse =
+            deps.querier
+                .query(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+                    contract_addr: pair.contract_addr.clone(),
+                }))?;
+
+        if contract_info.code_id != config.pair_code_id {
+            pairs.push(pair);
+        }
+    }
+
+    Ok(OutdatedPairsResponse {
+        pairs,
+        last_scanned,
+    })
+}
+
+pub fn query_creators(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CreatorsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_canonicalize(&start_after)?),
+        None => None,
+    };
+
+    let creators = read_creators(deps.storage, deps.api, start_after, limit)?;
+
+    Ok(CreatorsResponse { creators })
+}
+
+pub fn query_allowed_assets(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<AllowedAssetsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(start_after.to_raw(deps.api)?),
+        None => None,
+    };
+
+    let asset_infos = read_assets(deps.storage, deps.api, &ALLOWED_ASSETS, start_after, limit)?;
+
+    Ok(AllowedAssetsResponse { asset_infos })
+}
+
+pub fn query_blacklisted_assets(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<BlacklistedAssetsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(start_after.to_raw(deps.api)?),
+        None => None,
+    };
+
+    let asset_infos = read_assets(
+        deps.storage,
+        deps.api,
+        &BLACKLISTED_ASSETS,
+        start_after,
+        limit,
+    )?;
+
+    Ok(BlacklistedAssetsResponse { asset_infos })
+}
+
+pub fn query_deregistered_pairs(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_canonicalize(&start_after)?),
+        None => None,
+    };
+
+    let pairs = read_deregistered_pairs(deps.storage, deps.api, start_after, limit)?;
+
+    Ok(PairsResponse {
+        pairs,
+        metadata: None,
+    })
+}
+
+pub fn query_predict_pair_address(
+    deps: Deps,
+    env: Env,
+    asset_infos: [AssetInfo; 2],
+    fee_tier: Option<u16>,
+) -> StdResult<PairAddressResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let code_info = deps.querier.query_wasm_code_info(config.pair_code_id)?;
+
+    let pair_key = fee_tier_pair_key(
+        &[
+            asset_infos[0].to_raw(deps.api)?,
+            asset_infos[1].to_raw(deps.api)?,
+        ],
+        fee_tier.unwrap_or(DEFAULT_FEE_TIER),
+    );
+    let contract_addr = compute_pair_address(
+        &deps.api.addr_canonicalize(env.contract.address.as_str())?,
+        code_info.checksum.as_slice(),
+        &pair_key,
+        PAIR_GENERATIONS
+            .may_load(deps.storage, &pair_key)?
+            .unwrap_or_default(),
+    )?;
+
+    Ok(PairAddressResponse {
+        contract_addr: deps.api.addr_humanize(&contract_addr)?.to_string(),
+    })
+}
+
 pub fn query_native_token_decimal(
     deps: Deps,
     denom: String,
@@ -388,9 +1715,46 @@ pub fn query_native_token_decimal(
     Ok(NativeTokenDecimalsResponse { decimals })
 }
 
-const TARGET_CONTRACT_VERSION: &str = "0.1.0";
+pub fn query_pending_actions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingActionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let actions = PENDING_ACTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, pending_action) = item?;
+            Ok(PendingActionResponse {
+                id,
+                action: pending_action.action,
+                eta: pending_action.eta,
+            })
+        })
+        .collect::<StdResult<Vec<PendingActionResponse>>>()?;
+
+    Ok(PendingActionsResponse { actions })
+}
+
+const TARGET_CONTRACT_VERSION: &str = "0.2.0";
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // the single pending creation is replaced by the ones keyed by reply id
+    deps.storage.remove(b"tmp_pair_info");
+
+    // the secondary indexes of the existing pairs are filled page by page
+    // with BackfillIndexes
+    if PAIRS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+    {
+        INDEX_BACKFILL_CURSOR.save(deps.storage, &vec![])?;
+    }
+
     migrate_version(
         deps,
         TARGET_CONTRACT_VERSION,
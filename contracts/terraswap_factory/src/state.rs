@@ -1,40 +1,199 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Api, CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_std::{Addr, Api, CanonicalAddr, Decimal, Empty, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
-use terraswap::asset::{AssetInfoRaw, AssetRaw, PairInfo, PairInfoRaw};
+use terraswap::asset::{AssetInfo, AssetInfoRaw, AssetRaw, PairInfo, PairInfoRaw};
+use terraswap::factory::{
+    AssetMetadata, AssetMetadataResponse, PairMetadata, PairMetadataResponse, TimelockAction,
+};
+use terraswap::util::default_fee_tier;
+pub use terraswap::util::pair_key;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    #[serde(default)]
+    pub max_referral_commission: Decimal,
+    #[serde(default)]
+    pub timelock_delay: u64,
+    #[serde(default)]
+    pub creation_fee: Option<AssetRaw>,
+    #[serde(default)]
+    pub fee_recipient: Option<CanonicalAddr>,
+    #[serde(default = "default_permissionless")]
+    pub permissionless: bool,
+    #[serde(default)]
+    pub restrict_assets: bool,
+    #[serde(default)]
+    pub guardian: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+fn default_permissionless() -> bool {
+    true
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+// key : creator address
+pub const CREATORS: Map<&[u8], Empty> = Map::new("creators");
+// key : asset info
+pub const ALLOWED_ASSETS: Map<&[u8], AssetInfoRaw> = Map::new("allowed_assets");
+// key : asset info
+pub const BLACKLISTED_ASSETS: Map<&[u8], AssetInfoRaw> = Map::new("blacklisted_assets");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingAction {
+    pub action: TimelockAction,
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OwnershipProposal {
+    pub owner: CanonicalAddr,
+    pub expires_at: u64,
+}
+
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+pub const LAST_ACTION_ID: Item<u64> = Item::new("last_action_id");
+pub const PENDING_ACTIONS: Map<u64, PendingAction> = Map::new("pending_actions");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
     pub assets: [AssetRaw; 2],
     pub asset_decimals: [u8; 2],
     pub sender: Addr,
+    /// The creation fee held by the factory until the reply
+    #[serde(default)]
+    pub creation_fee: Option<AssetRaw>,
+    #[serde(default = "default_fee_tier")]
+    pub fee_tier: u16,
 }
 
-pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
+// key : reply id of the pair instantiation
+pub const TMP_PAIR_INFOS: Map<u64, TmpPairInfo> = Map::new("tmp_pair_infos");
+pub const LAST_REPLY_ID: Item<u64> = Item::new("last_reply_id");
 pub const PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
 
-pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
-    let mut asset_infos = asset_infos.to_vec();
-    asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+// key : (asset info, pair key)
+pub const PAIRS_BY_ASSET: Map<(&[u8], &[u8]), Empty> = Map::new("pairs_by_asset");
+
+// key : pair contract address / value: pair key
+pub const PAIRS_BY_ADDRESS: Map<&[u8], Vec<u8>> = Map::new("pairs_by_address");
+// key : liquidity token address / value: pair key
+pub const PAIRS_BY_LIQUIDITY_TOKEN: Map<&[u8], Vec<u8>> = Map::new("pairs_by_liquidity_token");
+
+// key of the last pair indexed by BackfillIndexes, only set while the
+// pairs registered before the indexes are left to backfill
+pub const INDEX_BACKFILL_CURSOR: Item<Vec<u8>> = Item::new("index_backfill_cursor");
+
+// key : pair key
+pub const PAIR_METADATA: Map<&[u8], PairMetadata> = Map::new("pair_metadata");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AssetMetadataRaw {
+    pub asset_info: AssetInfoRaw,
+    pub metadata: AssetMetadata,
+}
+
+// key : asset info
+pub const ASSET_METADATA: Map<&[u8], AssetMetadataRaw> = Map::new("asset_metadata");
+
+// key : pair contract address
+pub const DEREGISTERED_PAIRS: Map<&[u8], PairInfoRaw> = Map::new("deregistered_pairs");
+
+// key : pair key / value: number of times the pair key was deregistered,
+// it is part of the instantiate2 salt so that the pair can be created again
+pub const PAIR_GENERATIONS: Map<&[u8], u32> = Map::new("pair_generations");
+
+pub fn store_pair(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    pair_info: &PairInfoRaw,
+) -> StdResult<()> {
+    PAIRS.save(storage, pair_key, pair_info)?;
+    index_pair(storage, pair_key, pair_info)
+}
+
+/// Fills the secondary indexes of a stored pair
+pub fn index_pair(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    pair_info: &PairInfoRaw,
+) -> StdResult<()> {
+    for asset_info in pair_info.asset_infos.iter() {
+        PAIRS_BY_ASSET.save(storage, (asset_info.as_bytes(), pair_key), &Empty {})?;
+    }
+
+    PAIRS_BY_ADDRESS.save(
+        storage,
+        pair_info.contract_addr.as_slice(),
+        &pair_key.to_vec(),
+    )?;
+    PAIRS_BY_LIQUIDITY_TOKEN.save(
+        storage,
+        pair_info.liquidity_token.as_slice(),
+        &pair_key.to_vec(),
+    )?;
 
-    [asset_infos[0].as_bytes(), asset_infos[1].as_bytes()].concat()
+    Ok(())
+}
+
+/// Moves a pair out of the registry and its indexes into the deregistered pairs
+pub fn deregister_pair(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    pair_info: &PairInfoRaw,
+) -> StdResult<()> {
+    PAIRS.remove(storage, pair_key);
+    for asset_info in pair_info.asset_infos.iter() {
+        PAIRS_BY_ASSET.remove(storage, (asset_info.as_bytes(), pair_key));
+    }
+    PAIRS_BY_ADDRESS.remove(storage, pair_info.contract_addr.as_slice());
+    PAIRS_BY_LIQUIDITY_TOKEN.remove(storage, pair_info.liquidity_token.as_slice());
+    PAIR_METADATA.remove(storage, pair_key);
+
+    let generation = PAIR_GENERATIONS
+        .may_load(storage, pair_key)?
+        .unwrap_or_default();
+    PAIR_GENERATIONS.save(storage, pair_key, &(generation + 1))?;
+
+    DEREGISTERED_PAIRS.save(storage, pair_info.contract_addr.as_slice(), pair_info)
+}
+
+/// Finds a pair the backfill has not indexed yet, the pairs up to
+/// the backfill cursor are already indexed
+pub fn find_unindexed_pair(
+    storage: &dyn Storage,
+    predicate: impl Fn(&PairInfoRaw) -> bool,
+) -> StdResult<PairInfoRaw> {
+    if let Some(cursor) = INDEX_BACKFILL_CURSOR.may_load(storage)? {
+        let start = if cursor.is_empty() {
+            None
+        } else {
+            Some(Bound::ExclusiveRaw(cursor))
+        };
+
+        for item in PAIRS.range(storage, start, None, Order::Ascending) {
+            let (_, pair_info) = item?;
+            if predicate(&pair_info) {
+                return Ok(pair_info);
+            }
+        }
+    }
+
+    Err(StdError::not_found("pair"))
 }
 
 // settings for pagination
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
 pub fn read_pairs(
     storage: &dyn Storage,
     api: &dyn Api,
@@ -44,7 +203,85 @@ pub fn read_pairs(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
 
-    PAIRS
+    let pairs = PAIRS
+        .range(storage, start, None, Order::Ascending)
+        .map(|item| Ok((item?.1, ())));
+
+    take_pair_page(pairs, limit)?
+        .into_iter()
+        .map(|(v, _)| v.to_normal(api))
+        .collect::<StdResult<Vec<PairInfo>>>()
+}
+
+/// Pages continue after all the fee tiers of the `start_after` assets,
+/// so a page also takes the remaining tiers of its last asset pair
+pub fn take_pair_page<T>(
+    items: impl Iterator<Item = StdResult<(PairInfoRaw, T)>>,
+    limit: usize,
+) -> StdResult<Vec<(PairInfoRaw, T)>> {
+    let mut page: Vec<(PairInfoRaw, T)> = vec![];
+    for item in items {
+        let (pair_info, v) = item?;
+        if page.len() >= limit
+            && page.last().map(|(last, _)| pair_key(&last.asset_infos))
+                != Some(pair_key(&pair_info.asset_infos))
+        {
+            break;
+        }
+
+        page.push((pair_info, v));
+    }
+
+    Ok(page)
+}
+
+pub fn read_pairs_by_asset(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_info: &AssetInfoRaw,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
+
+    let pairs = PAIRS_BY_ASSET
+        .prefix(asset_info.as_bytes())
+        .keys(storage, start, None, Order::Ascending)
+        .map(|item| Ok((PAIRS.load(storage, &item?)?, ())));
+
+    take_pair_page(pairs, limit)?
+        .into_iter()
+        .map(|(v, _)| v.to_normal(api))
+        .collect::<StdResult<Vec<PairInfo>>>()
+}
+
+pub fn read_creators(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|creator| Bound::ExclusiveRaw(creator.to_vec()));
+
+    CREATORS
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(api.addr_humanize(&item?.into())?.to_string()))
+        .collect::<StdResult<Vec<String>>>()
+}
+
+pub fn read_deregistered_pairs(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|contract_addr| Bound::ExclusiveRaw(contract_addr.to_vec()));
+
+    DEREGISTERED_PAIRS
         .range(storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
@@ -54,8 +291,80 @@ pub fn read_pairs(
         .collect::<StdResult<Vec<PairInfo>>>()
 }
 
+pub fn read_pairs_metadata(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairMetadataResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
+
+    let pairs = PAIR_METADATA
+        .range(storage, start, None, Order::Ascending)
+        .map(|item| {
+            let (pair_key, metadata) = item?;
+            Ok((PAIRS.load(storage, &pair_key)?, metadata))
+        });
+
+    take_pair_page(pairs, limit)?
+        .into_iter()
+        .map(|(pair_info, metadata)| {
+            let pair_info = pair_info.to_normal(api)?;
+            Ok(PairMetadataResponse {
+                asset_infos: pair_info.asset_infos,
+                fee_tier: pair_info.fee_tier,
+                metadata,
+            })
+        })
+        .collect::<StdResult<Vec<PairMetadataResponse>>>()
+}
+
+pub fn read_assets_metadata(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<AssetInfoRaw>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AssetMetadataResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|asset_info| Bound::ExclusiveRaw(asset_info.as_bytes().to_vec()));
+
+    ASSET_METADATA
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(AssetMetadataResponse {
+                asset_info: v.asset_info.to_normal(api)?,
+                metadata: v.metadata,
+            })
+        })
+        .collect::<StdResult<Vec<AssetMetadataResponse>>>()
+}
+
+/// Reads a page of an asset list, like the allowed or the blacklisted assets
+pub fn read_assets(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    assets: &Map<&[u8], AssetInfoRaw>,
+    start_after: Option<AssetInfoRaw>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AssetInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|asset_info| Bound::ExclusiveRaw(asset_info.as_bytes().to_vec()));
+
+    assets
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            v.to_normal(api)
+        })
+        .collect::<StdResult<Vec<AssetInfo>>>()
+}
+
 // this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start(start_after: Option<[AssetInfoRaw; 2]>) -> Option<Vec<u8>> {
+pub fn calc_range_start(start_after: Option<[AssetInfoRaw; 2]>) -> Option<Vec<u8>> {
     start_after.map(|asset_infos| {
         let mut asset_infos = asset_infos.to_vec();
         asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
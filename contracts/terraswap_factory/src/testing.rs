@@ -1,22 +1,28 @@
-use crate::contract::{execute, instantiate, query, reply};
+use crate::contract::{execute, instantiate, migrate, query, reply};
 use terraswap::mock_querier::{mock_dependencies, WasmMockQuerier};
 
-use crate::state::{pair_key, TmpPairInfo, TMP_PAIR_INFO};
+use crate::state::{pair_key, store_pair, TmpPairInfo, PAIRS, TMP_PAIR_INFOS};
 
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coin, coins, from_binary, to_binary, Addr, CosmosMsg, OwnedDeps, Reply, ReplyOn,
-    Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, coin, coins, from_binary, to_binary, Addr, Api, BankMsg, CosmosMsg, Decimal, OwnedDeps,
+    Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
+use cw2::set_contract_version;
 use cw20::Cw20ExecuteMsg;
-use terraswap::asset::{Asset, AssetInfo, PairInfo};
+use terraswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
 use terraswap::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, NativeTokenDecimalsResponse, QueryMsg,
+    AllowedAssetsResponse, AssetMetadata, AssetMetadataResponse, AssetsMetadataResponse,
+    BlacklistedAssetsResponse, ConfigResponse, CreatorsResponse, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, NativeTokenDecimalsResponse, OutdatedPairsResponse, PairInfoMetadata, PairMetadata,
+    PairMetadataResponse, PairsMetadataResponse, PairsResponse, PairsWithPoolsResponse,
+    PendingActionResponse, PendingActionsResponse, QueryMsg, TimelockAction,
 };
 use terraswap::pair::{
     ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg,
-    MigrateMsg as PairMigrateMsg,
+    MigrateMsg as PairMigrateMsg, PoolResponse,
 };
+use terraswap::util::{fee_tier_pair_key, pair_salt};
 
 #[test]
 fn proper_initialization() {
@@ -25,6 +31,7 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        timelock_delay: 0u64,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -37,6 +44,8 @@ fn proper_initialization() {
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(321u64, config_res.pair_code_id);
     assert_eq!("addr0000".to_string(), config_res.owner);
+    assert_eq!(Decimal::zero(), config_res.max_referral_commission);
+    assert_eq!(0u64, config_res.timelock_delay);
 }
 
 #[test]
@@ -46,6 +55,7 @@ fn update_config() {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        timelock_delay: 86400u64,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -53,50 +63,187 @@ fn update_config() {
     // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // update owner
+    // update token code id
     let info = mock_info("addr0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
-        owner: Some("addr0001".to_string()),
         pair_code_id: None,
-        token_code_id: None,
+        token_code_id: Some(124u64),
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(0, res.messages.len());
+    let eta = mock_env().block.time.seconds() + 86400;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "queue_action"),
+            attr("id", "1"),
+            attr("eta", eta.to_string()),
+        ]
+    );
 
-    // it worked, let's query the state
+    // the update is queued
     let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config_res: ConfigResponse = from_binary(&query_res).unwrap();
     assert_eq!(123u64, config_res.token_code_id);
+    assert_eq!(86400u64, config_res.timelock_delay);
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingActions {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pending_res: PendingActionsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        pending_res.actions,
+        vec![PendingActionResponse {
+            id: 1,
+            action: TimelockAction::UpdateConfig {
+                pair_code_id: None,
+                token_code_id: Some(124u64),
+                max_referral_commission: None,
+                timelock_delay: None,
+                creation_fee: None,
+                fee_recipient: None,
+                permissionless: None,
+                restrict_assets: None,
+                guardian: None,
+            },
+            eta,
+        }]
+    );
+
+    // the delay has not passed
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86399);
+    let info = mock_info("anyone", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(format!(
+            "pending action can be executed from {}",
+            eta
+        )))
+    );
+
+    // anyone can execute it afterwards
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "update_config"), attr("id", "1")]
+    );
+
+    // it worked, let's query the state
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(124u64, config_res.token_code_id);
     assert_eq!(321u64, config_res.pair_code_id);
-    assert_eq!("addr0001".to_string(), config_res.owner);
+    assert_eq!("addr0000".to_string(), config_res.owner);
+
+    // an executed action is removed from the queue
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ExecuteAction { id: 1 },
+    );
+    assert_eq!(res, Err(StdError::generic_err("pending action not found")));
 
     // update left items
-    let env = mock_env();
-    let info = mock_info("addr0001", &[]);
+    let info = mock_info("addr0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
-        owner: None,
         pair_code_id: Some(100u64),
         token_code_id: Some(200u64),
+        max_referral_commission: Some(Decimal::percent(1)),
+        timelock_delay: Some(3600u64),
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: None,
     };
 
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_eq!(0, res.messages.len());
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(86400);
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ExecuteAction { id: 2 },
+    )
+    .unwrap();
 
     // it worked, let's query the state
     let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config_res: ConfigResponse = from_binary(&query_res).unwrap();
     assert_eq!(200u64, config_res.token_code_id);
     assert_eq!(100u64, config_res.pair_code_id);
-    assert_eq!("addr0001".to_string(), config_res.owner);
+    assert_eq!("addr0000".to_string(), config_res.owner);
+    assert_eq!(Decimal::percent(1), config_res.max_referral_commission);
+    assert_eq!(3600u64, config_res.timelock_delay);
+
+    // max_referral_commission cannot exceed 1
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        pair_code_id: None,
+        token_code_id: None,
+        max_referral_commission: Some(Decimal::percent(101)),
+        timelock_delay: None,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(
+                msg,
+                "max_referral_commission must be less than or equal to 1"
+            )
+        }
+        _ => panic!("Must return generic error"),
+    }
 
     // Unauthorized err
     let env = mock_env();
-    let info = mock_info("addr0000", &[]);
+    let info = mock_info("addr0001", &[]);
     let msg = ExecuteMsg::UpdateConfig {
-        owner: None,
         pair_code_id: None,
         token_code_id: None,
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg);
@@ -106,12 +253,189 @@ fn update_config() {
     }
 }
 
+#[test]
+fn transfer_ownership() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: "addr0001".to_string(),
+        expires_in: 100,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    let expires_at = mock_env().block.time.seconds() + 100;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_new_owner"),
+            attr("owner", "addr0001"),
+            attr("expires_at", expires_at.to_string()),
+        ]
+    );
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!("addr0000".to_string(), config_res.owner);
+    assert_eq!(Some("addr0001".to_string()), config_res.pending_owner);
+
+    // only the proposed owner can accept
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // the proposal expires
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0001", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("ownership proposal expired"))
+    );
+
+    // the owner drops the proposal
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::DropOwnershipProposal {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "drop_ownership_proposal")]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("ownership proposal not found"))
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "accept_ownership"),
+            attr("owner", "addr0001"),
+        ]
+    );
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!("addr0001".to_string(), config_res.owner);
+    assert_eq!(None, config_res.pending_owner);
+}
+
+#[test]
+fn cancel_action() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let info = mock_info("addr0000", &[]);
+    for code_id in [100u64, 200u64] {
+        let msg = ExecuteMsg::MigratePair {
+            contract: "contract0000".to_string(),
+            code_id: Some(code_id),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::CancelAction { id: 1 },
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::CancelAction { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "cancel_action"), attr("id", "1")]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::CancelAction { id: 1 },
+    );
+    assert_eq!(res, Err(StdError::generic_err("pending action not found")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ExecuteAction { id: 1 },
+    );
+    assert_eq!(res, Err(StdError::generic_err("pending action not found")));
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingActions {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pending_res: PendingActionsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        pending_res.actions,
+        vec![PendingActionResponse {
+            id: 2,
+            action: TimelockAction::MigratePair {
+                contract: "contract0000".to_string(),
+                code_id: 200u64,
+            },
+            eta: mock_env().block.time.seconds(),
+        }]
+    );
+}
+
 fn init(
     mut deps: OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let msg = InstantiateMsg {
         pair_code_id: 321u64,
         token_code_id: 123u64,
+        timelock_delay: 0u64,
     };
 
     let env = mock_env();
@@ -150,6 +474,8 @@ fn create_pair() {
 
     let msg = ExecuteMsg::CreatePair {
         assets: assets.clone(),
+        label: None,
+        fee_tier: None,
     };
 
     let env = mock_env();
@@ -159,7 +485,8 @@ fn create_pair() {
         res.attributes,
         vec![
             attr("action", "create_pair"),
-            attr("pair", "uusd-asset0001")
+            attr("pair", "uusd-asset0001"),
+            attr("fee_tier", "30"),
         ]
     );
     assert_eq!(
@@ -167,8 +494,8 @@ fn create_pair() {
         vec![SubMsg {
             id: 1,
             gas_limit: None,
-            reply_on: ReplyOn::Success,
-            msg: WasmMsg::Instantiate {
+            reply_on: ReplyOn::Always,
+            msg: WasmMsg::Instantiate2 {
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos: [
                         AssetInfo::NativeToken {
@@ -179,13 +506,21 @@ fn create_pair() {
                         }
                     ],
                     token_code_id: 123u64,
-                    asset_decimals: [6u8, 8u8]
+                    asset_decimals: [6u8, 8u8],
+                    fee_tier: Some(30),
                 })
                 .unwrap(),
                 code_id: 321u64,
                 funds: vec![],
-                label: "pair".to_string(),
+                label: "terraswap pair uusd-mAAPL".to_string(),
                 admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+                salt: pair_salt(
+                    &pair_key(&[
+                        assets[0].info.to_raw(deps.as_ref().api).unwrap(),
+                        assets[1].info.to_raw(deps.as_ref().api).unwrap(),
+                    ]),
+                    0
+                ),
             }
             .into()
         },]
@@ -202,12 +537,14 @@ fn create_pair() {
     ];
 
     assert_eq!(
-        TMP_PAIR_INFO.load(&deps.storage).unwrap(),
+        TMP_PAIR_INFOS.load(&deps.storage, 1).unwrap(),
         TmpPairInfo {
             assets: raw_assets,
             pair_key: pair_key(&raw_infos),
             sender: Addr::unchecked("addr0000"),
-            asset_decimals: [6u8, 8u8]
+            asset_decimals: [6u8, 8u8],
+            creation_fee: None,
+            fee_tier: 30,
         }
     );
 }
@@ -241,6 +578,8 @@ fn create_pair_native_token_and_ibc_token() {
 
     let msg = ExecuteMsg::CreatePair {
         assets: assets.clone(),
+        label: None,
+        fee_tier: None,
     };
 
     let env = mock_env();
@@ -248,15 +587,19 @@ fn create_pair_native_token_and_ibc_token() {
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
     assert_eq!(
         res.attributes,
-        vec![attr("action", "create_pair"), attr("pair", "uusd-ibc/HASH")]
+        vec![
+            attr("action", "create_pair"),
+            attr("pair", "uusd-ibc/HASH"),
+            attr("fee_tier", "30"),
+        ]
     );
     assert_eq!(
         res.messages,
         vec![SubMsg {
             id: 1,
             gas_limit: None,
-            reply_on: ReplyOn::Success,
-            msg: WasmMsg::Instantiate {
+            reply_on: ReplyOn::Always,
+            msg: WasmMsg::Instantiate2 {
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos: [
                         AssetInfo::NativeToken {
@@ -267,13 +610,21 @@ fn create_pair_native_token_and_ibc_token() {
                         }
                     ],
                     token_code_id: 123u64,
-                    asset_decimals: [6u8, 6u8]
+                    asset_decimals: [6u8, 6u8],
+                    fee_tier: Some(30),
                 })
                 .unwrap(),
                 code_id: 321u64,
                 funds: vec![],
-                label: "pair".to_string(),
+                label: "terraswap pair uusd-ibc/HASH".to_string(),
                 admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+                salt: pair_salt(
+                    &pair_key(&[
+                        assets[0].info.to_raw(deps.as_ref().api).unwrap(),
+                        assets[1].info.to_raw(deps.as_ref().api).unwrap(),
+                    ]),
+                    0
+                ),
             }
             .into()
         },]
@@ -290,21 +641,24 @@ fn create_pair_native_token_and_ibc_token() {
     ];
 
     assert_eq!(
-        TMP_PAIR_INFO.load(&deps.storage).unwrap(),
+        TMP_PAIR_INFOS.load(&deps.storage, 1).unwrap(),
         TmpPairInfo {
             assets: raw_assets,
             pair_key: pair_key(&raw_infos),
             sender: Addr::unchecked("addr0000"),
-            asset_decimals: [6u8, 6u8]
+            asset_decimals: [6u8, 6u8],
+            creation_fee: None,
+            fee_tier: 30,
         }
     );
 }
 
 #[test]
-fn fail_to_create_same_pair() {
+fn create_pair_with_label() {
     let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
     deps = init(deps);
-
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
     let assets = [
         Asset {
             info: AssetInfo::NativeToken {
@@ -313,82 +667,425 @@ fn fail_to_create_same_pair() {
             amount: Uint128::zero(),
         },
         Asset {
-            info: AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
             },
             amount: Uint128::zero(),
         },
     ];
+    let label = |res: Response| match &res.messages.last().unwrap().msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate2 { label, .. }) => label.clone(),
+        _ => panic!("DO NOT ENTER HERE"),
+    };
 
-    let msg = ExecuteMsg::CreatePair { assets };
-
-    let env = mock_env();
-    let info = mock_info("addr0000", &[]);
+    let msg = |label: Option<String>| ExecuteMsg::CreatePair {
+        assets: assets.clone(),
+        label,
+        fee_tier: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg(Some(String::new())),
+    );
+    assert_eq!(res, Err(StdError::generic_err("label cannot be empty")));
 
-    match execute(deps.as_mut(), env, info, msg).unwrap_err() {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "same asset".to_string()),
-        _ => panic!("Must return generic error"),
-    }
-}
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg(Some("a".repeat(129))),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "label cannot be longer than 128 bytes"
+        ))
+    );
 
-#[test]
-fn fail_to_create_pair_with_unknown_denom() {
-    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
-    deps = init(deps);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg(Some("USD/AAPL".to_string())),
+    )
+    .unwrap();
+    assert_eq!(label(res), "USD/AAPL");
 
-    deps.querier
-        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+    // the registry symbol takes precedence over the token symbol
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAssetMetadata {
+            asset_info: assets[1].info.clone(),
+            metadata: Some(AssetMetadata {
+                symbol: "AAPL".to_string(),
+                name: "Apple".to_string(),
+                logo_url: None,
+                decimals: None,
+            }),
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg(None),
+    )
+    .unwrap();
+    assert_eq!(label(res), "terraswap pair uusd-AAPL");
+}
 
-    let assets = [
-        Asset {
-            info: AssetInfo::NativeToken {
-                denom: "uxxx".to_string(),
+fn create_pair_msg(asset_infos: &[AssetInfo; 2], amounts: [u128; 2]) -> ExecuteMsg {
+    ExecuteMsg::CreatePair {
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(amounts[0]),
             },
-            amount: Uint128::zero(),
-        },
-        Asset {
-            info: AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(amounts[1]),
             },
-            amount: Uint128::zero(),
-        },
-    ];
-
-    let msg = ExecuteMsg::CreatePair { assets };
-
-    let env = mock_env();
-    let info = mock_info("addr0000", &[]);
-
-    match execute(deps.as_mut(), env, info, msg).unwrap_err() {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "asset1 is invalid".to_string()),
-        _ => panic!("Must return generic error"),
+        ],
+        label: None,
+        fee_tier: None,
     }
 }
 
 #[test]
-fn fail_to_create_pair_with_unknown_token() {
-    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+fn create_pair_with_native_initial_deposit() {
+    let mut deps = mock_dependencies(&[]);
     deps = init(deps);
+    deps.querier.with_terraswap_factory(
+        &[],
+        &[("uusd".to_string(), 6u8), ("ibc/HASH".to_string(), 6u8)],
+    );
 
-    deps.querier
-        .with_terraswap_factory(&[], &[("uluna".to_string(), 6u8)]);
-
-    let assets = [
-        Asset {
-            info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            amount: Uint128::zero(),
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
         },
-        Asset {
-            info: AssetInfo::Token {
-                contract_addr: "terra123".to_string(),
-            },
-            amount: Uint128::zero(),
+        AssetInfo::NativeToken {
+            denom: "ibc/HASH".to_string(),
         },
     ];
+    let funds = [coin(2000u128, "ibc/HASH"), coin(2000u128, "uusd")];
 
-    let msg = ExecuteMsg::CreatePair { assets };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds[..1]),
+        create_pair_msg(&asset_infos, [0, 2000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial liquidity requires both assets"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds[..1]),
+        create_pair_msg(&asset_infos, [2000, 2000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial deposit of 2000uusd must be sent"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        create_pair_msg(&asset_infos, [1000, 1000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial liquidity must mint more than 1000 LP tokens, 1000 given"
+        ))
+    );
+
+    // without a creation fee only the deposit can be sent
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[coin(2000u128, "ibc/HASH"), coin(2500u128, "uusd")],
+        ),
+        create_pair_msg(&asset_infos, [2000, 2000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "sent 2500uusd exceeds the deposit of 2000uusd"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        create_pair_msg(&asset_infos, [0, 0]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "sent 2000ibc/HASH exceeds the deposit of 0ibc/HASH"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        create_pair_msg(&asset_infos, [2000, 2000]),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn create_pair_with_token_initial_deposit() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0001".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::from(2000u128))],
+        ),
+        (
+            &"asset0002".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::from(2000u128))],
+        ),
+    ]);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0002".to_string(),
+        },
+    ];
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        create_pair_msg(&asset_infos, [2000, 0]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial liquidity requires both assets"
+        ))
+    );
+
+    // sqrt(10 * 100000) = 1000
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        create_pair_msg(&asset_infos, [10, 100000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial liquidity must mint more than 1000 LP tokens, 1000 given"
+        ))
+    );
+
+    // the tokens are pulled in the reply, nothing is sent along
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        create_pair_msg(&asset_infos, [2000, 2000]),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn create_pair_with_mixed_initial_deposit() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2000u128, "uusd")),
+        create_pair_msg(&asset_infos, [2000, 0]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial liquidity requires both assets"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1999u128, "uusd")),
+        create_pair_msg(&asset_infos, [2000, 2000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial deposit of 2000uusd must be sent"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(999u128, "uusd")),
+        create_pair_msg(&asset_infos, [999, 1000]),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "initial liquidity must mint more than 1000 LP tokens, 999 given"
+        ))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2000u128, "uusd")),
+        create_pair_msg(&asset_infos, [2000, 2000]),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    let tmp_pair_info = TMP_PAIR_INFOS.load(&deps.storage, 1).unwrap();
+    assert_eq!(tmp_pair_info.assets[0].amount, Uint128::from(2000u128));
+    assert_eq!(tmp_pair_info.assets[1].amount, Uint128::from(2000u128));
+}
+
+#[test]
+fn fail_to_create_same_pair() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreatePair {
+        assets,
+        label: None,
+        fee_tier: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+
+    match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+        StdError::GenericErr { msg, .. } => assert_eq!(msg, "same asset".to_string()),
+        _ => panic!("Must return generic error"),
+    }
+}
+
+#[test]
+fn fail_to_create_pair_with_unknown_denom() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uxxx".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreatePair {
+        assets,
+        label: None,
+        fee_tier: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+
+    match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+        StdError::GenericErr { msg, .. } => assert_eq!(msg, "asset1 is invalid".to_string()),
+        _ => panic!("Must return generic error"),
+    }
+}
+
+#[test]
+fn fail_to_create_pair_with_unknown_token() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+
+    deps.querier
+        .with_terraswap_factory(&[], &[("uluna".to_string(), 6u8)]);
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "terra123".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreatePair {
+        assets,
+        label: None,
+        fee_tier: None,
+    };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -437,14 +1134,17 @@ fn reply_only_create_pair() {
     ];
 
     let pair_key = pair_key(&raw_infos);
-    TMP_PAIR_INFO
+    TMP_PAIR_INFOS
         .save(
             &mut deps.storage,
+            1,
             &TmpPairInfo {
                 assets: raw_assets,
                 pair_key,
                 sender: Addr::unchecked("addr0000"),
                 asset_decimals: [8u8, 8u8],
+                creation_fee: None,
+                fee_tier: 30,
             },
         )
         .unwrap();
@@ -471,10 +1171,11 @@ fn reply_only_create_pair() {
         &[(
             &"0000".to_string(),
             &PairInfo {
-                asset_infos,
+                asset_infos: asset_infos.clone(),
                 contract_addr: "0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [8u8, 8u8],
+                fee_tier: 30,
             },
         )],
         &[],
@@ -488,6 +1189,52 @@ fn reply_only_create_pair() {
         res.attributes[1],
         attr("liquidity_token_addr", "liquidity0000")
     );
+
+    // the pair is indexed by each asset
+    for asset_info in asset_infos.iter() {
+        let res: PairsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PairsByAsset {
+                    asset_info: asset_info.clone(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.pairs.len(), 1);
+        assert_eq!(res.pairs[0].contract_addr, "0000");
+    }
+
+    // and by its addresses
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByAddress {
+                contract_addr: "0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.liquidity_token, "liquidity0000");
+
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByLiquidityToken {
+                liquidity_token: "liquidity0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.contract_addr, "0000");
 }
 
 #[test]
@@ -528,14 +1275,17 @@ fn reply_create_pair_with_provide() {
     ];
 
     let pair_key = pair_key(&raw_infos);
-    TMP_PAIR_INFO
+    TMP_PAIR_INFOS
         .save(
             &mut deps.storage,
+            1,
             &TmpPairInfo {
                 assets: raw_assets,
                 pair_key,
                 sender: Addr::unchecked("addr0000"),
                 asset_decimals: [18u8, 8u8],
+                creation_fee: None,
+                fee_tier: 30,
             },
         )
         .unwrap();
@@ -566,6 +1316,7 @@ fn reply_create_pair_with_provide() {
                 contract_addr: "pair0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [18u8, 8u8],
+                fee_tier: 30,
             },
         )],
         &[("uluna".to_string(), 18u8)],
@@ -637,27 +1388,787 @@ fn reply_create_pair_with_provide() {
 }
 
 #[test]
-fn failed_reply_with_unknown_id() {
+fn create_multiple_pairs_in_one_execution() {
     let mut deps = mock_dependencies(&[]);
-
-    let res = reply(
-        deps.as_mut(),
-        mock_env(),
-        Reply {
-            id: 9,
-            result: SubMsgResult::Ok(SubMsgResponse {
-                events: vec![],
-                data: Some(vec![].into()),
-            }),
-        },
-    );
-
-    assert_eq!(res, Err(StdError::generic_err("invalid reply msg")))
-}
-
-#[test]
-fn normal_add_allow_native_token() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+        (
+            &"asset0001".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+    ]);
+
+    let pair_0_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let pair_1_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    deps.querier.with_terraswap_factory(
+        &[
+            (
+                &"pair0000".to_string(),
+                &PairInfo {
+                    asset_infos: pair_0_infos.clone(),
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: "liquidity0000".to_string(),
+                    asset_decimals: [6u8, 8u8],
+                    fee_tier: 30,
+                },
+            ),
+            (
+                &"pair0001".to_string(),
+                &PairInfo {
+                    asset_infos: pair_1_infos.clone(),
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: "liquidity0001".to_string(),
+                    asset_decimals: [8u8, 8u8],
+                    fee_tier: 30,
+                },
+            ),
+        ],
+        &[("uusd".to_string(), 6u8)],
+    );
+
+    // both creations are pending before any reply
+    let mut reply_ids = vec![];
+    for asset_infos in [pair_0_infos.clone(), pair_1_infos.clone()].iter() {
+        let msg = ExecuteMsg::CreatePair {
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::zero(),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::zero(),
+                },
+            ],
+            label: None,
+            fee_tier: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        reply_ids.push(res.messages[0].id);
+    }
+    assert_eq!(reply_ids, vec![1, 2]);
+
+    // replies in any order register the right pairs
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 2,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 49].into()),
+            }),
+        },
+    )
+    .unwrap();
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+            }),
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: pair_0_infos,
+            fee_tier: None,
+        },
+    )
+    .unwrap();
+    let pair_res: PairInfo = from_binary(&query_res).unwrap();
+    assert_eq!(pair_res.contract_addr, "pair0000");
+    assert_eq!(pair_res.liquidity_token, "liquidity0000");
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: pair_1_infos,
+            fee_tier: None,
+        },
+    )
+    .unwrap();
+    let pair_res: PairInfo = from_binary(&query_res).unwrap();
+    assert_eq!(pair_res.contract_addr, "pair0001");
+    assert_eq!(pair_res.liquidity_token, "liquidity0001");
+
+    // the pending creations are cleaned up
+    assert!(TMP_PAIR_INFOS.may_load(&deps.storage, 1).unwrap().is_none());
+    assert!(TMP_PAIR_INFOS.may_load(&deps.storage, 2).unwrap().is_none());
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+            }),
+        },
+    );
+    assert_eq!(res, Err(StdError::generic_err("invalid reply msg")));
+}
+
+#[test]
+fn failed_reply_with_unknown_id() {
+    let mut deps = mock_dependencies(&[]);
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 9,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![].into()),
+            }),
+        },
+    );
+
+    assert_eq!(res, Err(StdError::generic_err("invalid reply msg")))
+}
+
+#[test]
+fn create_pair_with_native_creation_fee() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        pair_code_id: None,
+        token_code_id: None,
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        }),
+        fee_recipient: Some("collector0000".to_string()),
+        permissionless: None,
+        restrict_assets: None,
+        guardian: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    )
+    .unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        config_res.creation_fee,
+        Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        })
+    );
+    assert_eq!(config_res.fee_recipient, Some("collector0000".to_string()));
+
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(2000u128),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::from(2000u128),
+        },
+    ];
+    let msg = ExecuteMsg::CreatePair {
+        assets: assets.clone(),
+        label: None,
+        fee_tier: None,
+    };
+
+    // the deposit does not count towards the fee
+    let info = mock_info("addr0000", &coins(2000u128, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "creation fee of 1000uusd is required"
+        ))
+    );
+
+    // the overpayment is not kept by the factory
+    let info = mock_info("addr0000", &coins(3500u128, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "sent 3500uusd exceeds the deposit and the creation fee of 1000uusd"
+        ))
+    );
+
+    let info = mock_info("addr0000", &coins(3000u128, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+    assert_eq!(
+        TMP_PAIR_INFOS.load(&deps.storage, 1).unwrap().creation_fee,
+        Some(
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(1000u128),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap()
+        )
+    );
+
+    // a failed instantiation refunds the fee and the native deposit
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Err("instantiate failed".to_string()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "refund_create_pair"),
+            attr("sender", "addr0000"),
+            attr("error", "instantiate failed"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(2000u128, "uusd"),
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(1000u128, "uusd"),
+            }),
+        ]
+    );
+    assert!(TMP_PAIR_INFOS.may_load(&deps.storage, 1).unwrap().is_none());
+}
+
+#[test]
+fn create_pair_with_token_creation_fee() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        pair_code_id: None,
+        token_code_id: None,
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: Some(Asset {
+            info: AssetInfo::Token {
+                contract_addr: "fee0000".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        }),
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    )
+    .unwrap();
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+        (
+            &"asset0001".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::zero())],
+        ),
+    ]);
+
+    let assets = [
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+    ];
+
+    // a cw20 fee does not allow native funds
+    let info = mock_info("addr0001", &coins(1000u128, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CreatePair {
+            assets: assets.clone(),
+            label: None,
+            fee_tier: None,
+        },
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "sent 1000uusd exceeds the deposit of 0uusd"
+        ))
+    );
+
+    // the fee is pulled before the instantiation
+    let info = mock_info("addr0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CreatePair {
+            assets: assets.clone(),
+            label: None,
+            fee_tier: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "fee0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "addr0001".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::from(1000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // register terraswap pair querier
+    deps.querier.with_terraswap_factory(
+        &[(
+            &"pair0000".to_string(),
+            &PairInfo {
+                asset_infos: [assets[0].info.clone(), assets[1].info.clone()],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: "liquidity0000".to_string(),
+                asset_decimals: [8u8, 8u8],
+                fee_tier: 30,
+            },
+        )],
+        &[],
+    );
+
+    // the fee goes to the owner without a fee recipient
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "fee0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+#[test]
+fn pause_and_unpause() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        pair_code_id: None,
+        token_code_id: None,
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: Some("guardian0000".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    )
+    .unwrap();
+
+    // only the owner or the guardian can pause
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::Pause {},
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian0000", &[]),
+        ExecuteMsg::Pause {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "pause")]);
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.guardian, Some("guardian0000".to_string()));
+    assert!(config_res.paused);
+
+    let msg = ExecuteMsg::CreatePair {
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+        ],
+        label: None,
+        fee_tier: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("factory is paused")));
+
+    // the guardian cannot unpause
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian0000", &[]),
+        ExecuteMsg::Unpause {},
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Unpause {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "unpause")]);
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert!(!config_res.paused);
+
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // an empty guardian removes it
+    let msg = ExecuteMsg::UpdateConfig {
+        pair_code_id: None,
+        token_code_id: None,
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: None,
+        restrict_assets: None,
+        guardian: Some("".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteAction { id: 2 },
+    )
+    .unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.guardian, None);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian0000", &[]),
+        ExecuteMsg::Pause {},
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+}
+
+#[test]
+fn permissioned_create_pair() {
+    let mut deps = mock_dependencies(&[coin(10u128, "uusd".to_string())]);
+    deps = init(deps);
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        pair_code_id: None,
+        token_code_id: None,
+        max_referral_commission: None,
+        timelock_delay: None,
+        creation_fee: None,
+        fee_recipient: None,
+        permissionless: Some(false),
+        restrict_assets: Some(true),
+        guardian: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    )
+    .unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert!(!config_res.permissionless);
+    assert!(config_res.restrict_assets);
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let msg = ExecuteMsg::CreatePair {
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::zero(),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::zero(),
+            },
+        ],
+        label: None,
+        fee_tier: None,
+    };
+
+    // only the owner manages the allowlists
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::UpdateCreators {
+            add: vec!["addr0001".to_string()],
+            remove: vec![],
+        },
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateCreators {
+            add: vec!["addr0001".to_string(), "addr0002".to_string()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Creators {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let creators_res: CreatorsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        creators_res.creators,
+        vec!["addr0001".to_string(), "addr0002".to_string()]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Creators {
+            start_after: Some("addr0001".to_string()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let creators_res: CreatorsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(creators_res.creators, vec!["addr0002".to_string()]);
+
+    // asset0001 is not allowed yet
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("uusd is not allowed")));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAllowedAssets {
+            add: asset_infos.to_vec(),
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllowedAssets {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let allowed_res: AllowedAssetsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(allowed_res.asset_infos.len(), 2);
+    assert!(allowed_res.asset_infos.contains(&asset_infos[0]));
+    assert!(allowed_res.asset_infos.contains(&asset_infos[1]));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    // a removed creator cannot create pairs anymore
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateCreators {
+            add: vec![],
+            remove: vec!["addr0001".to_string()],
+        },
+    )
+    .unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg);
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+}
+
+#[test]
+fn normal_add_allow_native_token() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+        decimals: 6u8,
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
+        Response::new().add_attributes(vec![
+            ("action", "add_allow_native_token"),
+            ("denom", "uluna"),
+            ("decimals", "6"),
+        ])
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uluna".to_string(),
+        },
+    )
+    .unwrap();
+    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    assert_eq!(6u8, res.decimals)
+}
+
+#[test]
+fn failed_add_allow_native_token_with_non_admin() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
     deps = init(deps);
 
     let msg = ExecuteMsg::AddNativeTokenDecimals {
@@ -665,170 +2176,1269 @@ fn normal_add_allow_native_token() {
         decimals: 6u8,
     };
 
+    let info = mock_info("noadmin", &[]);
+
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err("unauthorized"))
+    );
+}
+
+#[test]
+fn failed_add_allow_native_token_with_zero_factory_balance() {
+    let mut deps = mock_dependencies(&[coin(0u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+        decimals: 6u8,
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err(
+            "a balance greater than zero is required by the factory for verification",
+        ))
+    );
+}
+
+#[test]
+fn append_add_allow_native_token_with_already_exist_token() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+
+        decimals: 6u8,
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uluna".to_string(),
+        },
+    )
+    .unwrap();
+    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    assert_eq!(6u8, res.decimals);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+        decimals: 7u8,
+    };
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uluna".to_string(),
+        },
+    )
+    .unwrap();
+    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
+    assert_eq!(7u8, res.decimals)
+}
+
+#[test]
+fn normal_migrate_pair() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::MigratePair {
+        code_id: Some(123u64),
+        contract: "contract0000".to_string(),
+    };
+
     let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
     assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
-        Response::new().add_attributes(vec![
-            ("action", "add_allow_native_token"),
-            ("denom", "uluna"),
-            ("decimals", "6"),
-        ])
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::ExecuteAction { id: 1 }
+        )
+        .unwrap(),
+        Response::new()
+            .add_attribute("action", "migrate_pair")
+            .add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: "contract0000".to_string(),
+                new_code_id: 123u64,
+                msg: to_binary(&PairMigrateMsg {}).unwrap(),
+            }))
+            .add_attribute("id", "1"),
+    );
+}
+
+#[test]
+fn normal_migrate_pair_with_none_code_id_will_config_code_id() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::MigratePair {
+        code_id: None,
+        contract: "contract0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::ExecuteAction { id: 1 }
+        )
+        .unwrap(),
+        Response::new()
+            .add_attribute("action", "migrate_pair")
+            .add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: "contract0000".to_string(),
+                new_code_id: 321u64,
+                msg: to_binary(&PairMigrateMsg {}).unwrap(),
+            }))
+            .add_attribute("id", "1"),
+    );
+}
+
+#[test]
+fn failed_migrate_pair_with_no_admin() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    let msg = ExecuteMsg::MigratePair {
+        code_id: None,
+        contract: "contract0000".to_string(),
+    };
+
+    let info = mock_info("noadmin", &[]);
+
+    assert_eq!(
+        execute(deps.as_mut(), mock_env(), info, msg),
+        Err(StdError::generic_err("unauthorized")),
+    );
+}
+
+#[test]
+fn migrate_pairs() {
+    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+    deps = init(deps);
+
+    // pair0000 is already on the current pair_code_id
+    for (i, code_id) in [321u64, 100u64, 100u64].iter().enumerate() {
+        let asset_infos = [
+            AssetInfo::Token {
+                contract_addr: format!("asset000{}", i),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ];
+        let raw_infos = [
+            asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+            asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+        ];
+        let contract_addr = format!("pair000{}", i);
+        let pair_info = PairInfoRaw {
+            asset_infos: raw_infos.clone(),
+            contract_addr: deps.api.addr_canonicalize(&contract_addr).unwrap(),
+            liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+            asset_decimals: [6u8, 6u8],
+            fee_tier: 30,
+        };
+        PAIRS
+            .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+            .unwrap();
+        deps.querier.with_code_ids(&[(&contract_addr, *code_id)]);
+    }
+
+    let all_pairs: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+                with_metadata: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let outdated_pairs: Vec<PairInfo> = all_pairs
+        .pairs
+        .iter()
+        .filter(|pair| pair.contract_addr != "pair0000")
+        .cloned()
+        .collect();
+
+    let res: OutdatedPairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OutdatedPairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.pairs, outdated_pairs);
+    assert_eq!(
+        res.last_scanned,
+        Some(all_pairs.pairs[2].asset_infos.clone())
+    );
+
+    // the scan is paginated, an up to date page is empty
+    let mut start_after = None;
+    let mut scanned_outdated_pairs: Vec<PairInfo> = vec![];
+    for pair in all_pairs.pairs.iter() {
+        let res: OutdatedPairsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OutdatedPairs {
+                    start_after,
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.pairs.is_empty(), pair.contract_addr == "pair0000");
+        assert_eq!(res.last_scanned, Some(pair.asset_infos.clone()));
+
+        scanned_outdated_pairs.extend(res.pairs);
+        start_after = res.last_scanned;
+    }
+    assert_eq!(scanned_outdated_pairs, outdated_pairs);
+
+    let res: OutdatedPairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OutdatedPairs {
+                start_after,
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.pairs.is_empty());
+    assert_eq!(res.last_scanned, None);
+
+    let msg = ExecuteMsg::MigratePairs {
+        start_after: None,
+        limit: Some(2),
+        code_id: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("noadmin", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::ExecuteAction { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        all_pairs.pairs[..2]
+            .iter()
+            .map(|pair| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: pair.contract_addr.to_string(),
+                new_code_id: 321u64,
+                msg: to_binary(&PairMigrateMsg {}).unwrap(),
+            })))
+            .collect::<Vec<SubMsg>>()
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_pairs"),
+            attr("count", "2"),
+            attr("id", "1"),
+        ]
+    );
+
+    // the next page
+    let msg = ExecuteMsg::MigratePairs {
+        start_after: Some(all_pairs.pairs[1].asset_infos.clone()),
+        limit: Some(2),
+        code_id: Some(322u64),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ExecuteAction { id: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: all_pairs.pairs[2].contract_addr.to_string(),
+            new_code_id: 322u64,
+            msg: to_binary(&PairMigrateMsg {}).unwrap(),
+        }))]
+    );
+}
+
+#[test]
+fn pair_indexes_backfilled_on_migrate() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    // the version stored by the deployed factories
+    set_contract_version(
+        deps.as_mut().storage,
+        "crates.io:terraswap-factory",
+        "0.2.0",
+    )
+    .unwrap();
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    // pairs stored before the index existed
+    for i in 0..2 {
+        let raw_infos = [
+            AssetInfo::Token {
+                contract_addr: format!("asset000{}", i),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+            uusd.to_raw(deps.as_ref().api).unwrap(),
+        ];
+        let pair_info = PairInfoRaw {
+            asset_infos: raw_infos.clone(),
+            contract_addr: deps
+                .api
+                .addr_canonicalize(&format!("pair000{}", i))
+                .unwrap(),
+            liquidity_token: deps
+                .api
+                .addr_canonicalize(&format!("liquidity000{}", i))
+                .unwrap(),
+            asset_decimals: [6u8, 6u8],
+            fee_tier: 30,
+        };
+        PAIRS
+            .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+            .unwrap();
+    }
+
+    let query_pairs_by_asset =
+        |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+         asset_info: &AssetInfo,
+         start_after: Option<[AssetInfo; 2]>| {
+            let res: PairsResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::PairsByAsset {
+                        asset_info: asset_info.clone(),
+                        start_after,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.pairs
+        };
+
+    assert_eq!(query_pairs_by_asset(&deps, &uusd, None), vec![]);
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    // the indexes are incomplete until the backfill is done
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairsByAsset {
+            asset_info: uusd.clone(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "pair indexes are not backfilled yet")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::BackfillIndexes { limit: Some(1) };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // a page per execution, the last one is done when it falls short of the limit
+    for (indexed_pairs, done) in [("1", "false"), ("1", "false"), ("0", "true")] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "backfill_indexes"),
+                attr("indexed_pairs", indexed_pairs),
+                attr("done", done),
+            ]
+        );
+    }
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "pair indexes are already backfilled")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let pairs = query_pairs_by_asset(&deps, &uusd, None);
+    assert_eq!(pairs.len(), 2);
+
+    let next_pairs = query_pairs_by_asset(&deps, &uusd, Some(pairs[0].asset_infos.clone()));
+    assert_eq!(next_pairs, pairs[1..].to_vec());
+
+    let asset_info = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let pairs = query_pairs_by_asset(&deps, &asset_info, None);
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].contract_addr, "pair0001");
+
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByAddress {
+                contract_addr: "pair0001".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, pairs[0]);
+
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByLiquidityToken {
+                liquidity_token: "liquidity0001".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, pairs[0]);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByAddress {
+            contract_addr: "pair0002".to_string(),
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn pair_lookups_after_migrate() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    // the version stored by the deployed factories
+    set_contract_version(
+        deps.as_mut().storage,
+        "crates.io:terraswap-factory",
+        "0.2.0",
+    )
+    .unwrap();
+
+    // pairs stored before the indexes existed
+    for i in 0..3 {
+        let raw_infos = [
+            AssetInfo::Token {
+                contract_addr: format!("asset000{}", i),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+        ];
+        let pair_info = PairInfoRaw {
+            asset_infos: raw_infos.clone(),
+            contract_addr: deps
+                .api
+                .addr_canonicalize(&format!("pair000{}", i))
+                .unwrap(),
+            liquidity_token: deps
+                .api
+                .addr_canonicalize(&format!("liquidity000{}", i))
+                .unwrap(),
+            asset_decimals: [6u8, 6u8],
+            fee_tier: 30,
+        };
+        PAIRS
+            .save(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info)
+            .unwrap();
+    }
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let assert_lookups = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>| {
+        for i in 0..3 {
+            let res: PairInfo = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::PairByAddress {
+                        contract_addr: format!("pair000{}", i),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(res.liquidity_token, format!("liquidity000{}", i));
+
+            let res: PairInfo = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::PairByLiquidityToken {
+                        liquidity_token: format!("liquidity000{}", i),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(res.contract_addr, format!("pair000{}", i));
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairByAddress {
+                contract_addr: "pair0003".to_string(),
+            },
+        );
+        assert!(res.is_err());
+    };
+
+    // the pairs are found before, during and after the backfill
+    assert_lookups(&deps);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::BackfillIndexes { limit: Some(2) },
+    )
+    .unwrap();
+    assert_lookups(&deps);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::BackfillIndexes { limit: Some(2) },
+    )
+    .unwrap();
+    assert_lookups(&deps);
+}
+
+#[test]
+fn deregister_and_blacklist_pair() {
+    let mut deps = mock_dependencies(&[]);
+    deps = init(deps);
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
+        liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+        asset_decimals: [8u8, 6u8],
+        fee_tier: 30,
+    };
+    store_pair(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info).unwrap();
+
+    let msg = ExecuteMsg::DeregisterPair {
+        asset_infos: asset_infos.clone(),
+        fee_tier: None,
+    };
+
+    // only the owner can deregister
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deregister_pair"),
+            attr("pair", "asset0001-uusd"),
+            attr("pair_contract_addr", "pair0000"),
+        ]
     );
 
-    let res = query(
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(res, Err(StdError::generic_err("pair not found")));
+
+    // the pair and its indexes are gone
+    assert!(query(
         deps.as_ref(),
         mock_env(),
-        QueryMsg::NativeTokenDecimals {
-            denom: "uluna".to_string(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+            fee_tier: None,
+        },
+    )
+    .is_err());
+    assert!(query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByAddress {
+            contract_addr: "pair0000".to_string(),
         },
     )
+    .is_err());
+    assert!(query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByLiquidityToken {
+            liquidity_token: "liquidity0000".to_string(),
+        },
+    )
+    .is_err());
+    let res: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsByAsset {
+                asset_info: asset_infos[1].clone(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
     .unwrap();
-    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
-    assert_eq!(6u8, res.decimals)
-}
+    assert!(res.pairs.is_empty());
+
+    // but kept as deregistered
+    let res: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DeregisteredPairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.pairs,
+        vec![pair_info.to_normal(deps.as_ref().api).unwrap()]
+    );
 
-#[test]
-fn failed_add_allow_native_token_with_non_admin() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
-    deps = init(deps);
+    // a blacklisted asset cannot be paired anymore
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::UpdateBlacklist {
+            add: vec![asset_infos[0].clone()],
+            remove: vec![],
+        },
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
 
-    let msg = ExecuteMsg::AddNativeTokenDecimals {
-        denom: "uluna".to_string(),
-        decimals: 6u8,
-    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateBlacklist {
+            add: vec![asset_infos[0].clone()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
 
-    let info = mock_info("noadmin", &[]);
+    let res: BlacklistedAssetsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BlacklistedAssets {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.asset_infos, vec![asset_infos[0].clone()]);
 
-    assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg),
-        Err(StdError::generic_err("unauthorized"))
+    let msg = ExecuteMsg::CreatePair {
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::zero(),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::zero(),
+            },
+        ],
+        label: None,
+        fee_tier: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
     );
+    assert_eq!(res, Err(StdError::generic_err("asset0001 is blacklisted")));
+
+    // the pair can be created again once unlisted,
+    // at another address than the deregistered one
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateBlacklist {
+            add: vec![],
+            remove: vec![asset_infos[0].clone()],
+        },
+    )
+    .unwrap();
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg).unwrap();
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate2 { salt, .. }) => {
+            assert_eq!(*salt, pair_salt(&pair_key(&raw_infos), 1));
+            assert_ne!(*salt, pair_salt(&pair_key(&raw_infos), 0));
+        }
+        _ => panic!("Must instantiate the pair"),
+    }
 }
 
 #[test]
-fn failed_add_allow_native_token_with_zero_factory_balance() {
-    let mut deps = mock_dependencies(&[coin(0u128, "uluna".to_string())]);
+fn pair_and_asset_metadata() {
+    let mut deps = mock_dependencies(&[]);
     deps = init(deps);
 
-    let msg = ExecuteMsg::AddNativeTokenDecimals {
-        denom: "uluna".to_string(),
-        decimals: 6u8,
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+    let pair_info = PairInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
+        liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+        asset_decimals: [8u8, 6u8],
+        fee_tier: 30,
     };
 
-    let info = mock_info("addr0000", &[]);
+    let pair_metadata = PairMetadata {
+        verified: true,
+        tags: vec!["stable".to_string()],
+    };
+    let msg = ExecuteMsg::UpdatePairMetadata {
+        asset_infos: asset_infos.clone(),
+        metadata: Some(pair_metadata.clone()),
+        fee_tier: None,
+    };
 
-    assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg),
-        Err(StdError::generic_err(
-            "a balance greater than zero is required by the factory for verification",
-        ))
+    // only registered pairs have metadata
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
     );
-}
-
-#[test]
-fn append_add_allow_native_token_with_already_exist_token() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
-    deps = init(deps);
+    assert_eq!(res, Err(StdError::generic_err("pair not found")));
 
-    let msg = ExecuteMsg::AddNativeTokenDecimals {
-        denom: "uluna".to_string(),
+    store_pair(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info).unwrap();
 
-        decimals: 6u8,
-    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
 
-    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
 
-    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    // decimals are only registered for native tokens
+    let usd_metadata = AssetMetadata {
+        symbol: "USD".to_string(),
+        name: "Terra USD".to_string(),
+        logo_url: Some("https://logo/usd.png".to_string()),
+        decimals: Some(6u8),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAssetMetadata {
+            asset_info: asset_infos[0].clone(),
+            metadata: Some(usd_metadata.clone()),
+        },
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(
+            "decimals can only be set for native tokens"
+        ))
+    );
 
-    let res = query(
-        deps.as_ref(),
+    execute(
+        deps.as_mut(),
         mock_env(),
-        QueryMsg::NativeTokenDecimals {
-            denom: "uluna".to_string(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAssetMetadata {
+            asset_info: asset_infos[1].clone(),
+            metadata: Some(usd_metadata.clone()),
         },
     )
     .unwrap();
-    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
-    assert_eq!(6u8, res.decimals);
 
-    let msg = ExecuteMsg::AddNativeTokenDecimals {
-        denom: "uluna".to_string(),
-        decimals: 7u8,
-    };
+    let res: PairsMetadataResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsMetadata {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.pairs,
+        vec![PairMetadataResponse {
+            asset_infos: asset_infos.clone(),
+            metadata: pair_metadata.clone(),
+            fee_tier: 30,
+        }]
+    );
 
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res: AssetsMetadataResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AssetsMetadata {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.assets,
+        vec![AssetMetadataResponse {
+            asset_info: asset_infos[1].clone(),
+            metadata: usd_metadata.clone(),
+        }]
+    );
 
-    let res = query(
-        deps.as_ref(),
+    let res: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+                with_metadata: Some(true),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        PairsResponse {
+            pairs: vec![pair_info.to_normal(deps.as_ref().api).unwrap()],
+            metadata: Some(vec![PairInfoMetadata {
+                metadata: Some(pair_metadata),
+                asset_metadata: [None, Some(usd_metadata)],
+            }]),
+        }
+    );
+
+    // the metadata goes away with the pair
+    execute(
+        deps.as_mut(),
         mock_env(),
-        QueryMsg::NativeTokenDecimals {
-            denom: "uluna".to_string(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::DeregisterPair {
+            asset_infos,
+            fee_tier: None,
         },
     )
     .unwrap();
-    let res: NativeTokenDecimalsResponse = from_binary(&res).unwrap();
-    assert_eq!(7u8, res.decimals)
+
+    let res: PairsMetadataResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsMetadata {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.pairs.is_empty());
 }
 
 #[test]
-fn normal_migrate_pair() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+fn pairs_with_pools() {
+    let mut deps = mock_dependencies(&[]);
     deps = init(deps);
 
-    let msg = ExecuteMsg::MigratePair {
-        code_id: Some(123u64),
-        contract: "contract0000".to_string(),
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
     };
+    let mut pools = vec![];
+    for i in 0..2u128 {
+        let asset_infos = [
+            AssetInfo::Token {
+                contract_addr: format!("asset000{}", i),
+            },
+            uusd.clone(),
+        ];
+        let raw_infos = [
+            asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+            asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+        ];
+        let pair_info = PairInfoRaw {
+            asset_infos: raw_infos.clone(),
+            contract_addr: deps
+                .api
+                .addr_canonicalize(&format!("pair000{}", i))
+                .unwrap(),
+            liquidity_token: deps
+                .api
+                .addr_canonicalize(&format!("liquidity000{}", i))
+                .unwrap(),
+            asset_decimals: [8u8, 6u8],
+            fee_tier: 30,
+        };
+        store_pair(deps.as_mut().storage, &pair_key(&raw_infos), &pair_info).unwrap();
+
+        pools.push((
+            format!("pair000{}", i),
+            PoolResponse {
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(100u128 * (i + 1)),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(200u128 * (i + 1)),
+                    },
+                ],
+                total_share: Uint128::from(141u128 * (i + 1)),
+            },
+        ));
+    }
+    deps.querier.with_pools(
+        &pools
+            .iter()
+            .map(|(pair, pool)| (pair, pool))
+            .collect::<Vec<(&String, &PoolResponse)>>(),
+    );
 
-    let info = mock_info("addr0000", &[]);
+    let res: PairsWithPoolsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsWithPools {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.pairs.len(), 2);
+    for pair in res.pairs.iter() {
+        let (_, pool) = pools
+            .iter()
+            .find(|(contract_addr, _)| *contract_addr == pair.pair.contract_addr)
+            .unwrap();
+        assert_eq!(pair.assets, pool.assets);
+        assert_eq!(pair.total_share, pool.total_share);
+    }
 
-    assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
-        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
-            contract_addr: "contract0000".to_string(),
-            new_code_id: 123u64,
-            msg: to_binary(&PairMigrateMsg {}).unwrap(),
-        })),
-    );
+    // paginated like the pairs query
+    let res: PairsWithPoolsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsWithPools {
+                start_after: Some(res.pairs[0].pair.asset_infos.clone()),
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.pairs.len(), 1);
 }
 
 #[test]
-fn normal_migrate_pair_with_none_code_id_will_config_code_id() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
+fn pairs_in_fee_tiers() {
+    let mut deps = mock_dependencies(&[]);
     deps = init(deps);
+    deps.querier
+        .with_terraswap_factory(&[], &[("uusd".to_string(), 6u8)]);
 
-    let msg = ExecuteMsg::MigratePair {
-        code_id: None,
-        contract: "contract0000".to_string(),
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+
+    // the default tier pair already exists
+    let default_pair = PairInfoRaw {
+        asset_infos: raw_infos.clone(),
+        contract_addr: deps.api.addr_canonicalize("pair0030").unwrap(),
+        liquidity_token: deps.api.addr_canonicalize("liquidity0030").unwrap(),
+        asset_decimals: [8u8, 6u8],
+        fee_tier: 30,
+    };
+    store_pair(
+        deps.as_mut().storage,
+        &fee_tier_pair_key(&raw_infos, 30),
+        &default_pair,
+    )
+    .unwrap();
+
+    let create_msg = |fee_tier: Option<u16>| ExecuteMsg::CreatePair {
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::zero(),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::zero(),
+            },
+        ],
+        fee_tier,
+        label: None,
     };
 
     let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), create_msg(None));
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Pair already exists"),
+        _ => panic!("Must return generic error"),
+    }
 
-    assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap(),
-        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
-            contract_addr: "contract0000".to_string(),
-            new_code_id: 321u64,
-            msg: to_binary(&PairMigrateMsg {}).unwrap(),
-        })),
-    );
-}
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), create_msg(Some(7)));
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "fee tier 7 is not supported"),
+        _ => panic!("Must return generic error"),
+    }
 
-#[test]
-fn failed_migrate_pair_with_no_admin() {
-    let mut deps = mock_dependencies(&[coin(1u128, "uluna".to_string())]);
-    deps = init(deps);
+    // another tier of the same assets gets its own key and address
+    let res = execute(deps.as_mut(), mock_env(), info, create_msg(Some(5))).unwrap();
+    assert!(res.attributes.contains(&attr("fee_tier", "5")));
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate2 { salt, .. }) => {
+            assert_eq!(*salt, pair_salt(&fee_tier_pair_key(&raw_infos, 5), 0));
+            assert_ne!(*salt, pair_salt(&pair_key(&raw_infos), 0));
+        }
+        _ => panic!("Must instantiate the pair"),
+    }
 
-    let msg = ExecuteMsg::MigratePair {
-        code_id: None,
-        contract: "contract0000".to_string(),
+    let tiered_pair = PairInfoRaw {
+        contract_addr: deps.api.addr_canonicalize("pair0005").unwrap(),
+        liquidity_token: deps.api.addr_canonicalize("liquidity0005").unwrap(),
+        fee_tier: 5,
+        ..default_pair.clone()
     };
+    store_pair(
+        deps.as_mut().storage,
+        &fee_tier_pair_key(&raw_infos, 5),
+        &tiered_pair,
+    )
+    .unwrap();
 
-    let info = mock_info("noadmin", &[]);
-
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+                fee_tier: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, default_pair.to_normal(deps.as_ref().api).unwrap());
+
+    let res: PairInfo = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+                fee_tier: Some(5),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, tiered_pair.to_normal(deps.as_ref().api).unwrap());
+
+    let res: PairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairsForAssets {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
     assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg),
-        Err(StdError::generic_err("unauthorized")),
+        res.pairs.iter().map(|p| p.fee_tier).collect::<Vec<u16>>(),
+        vec![5, 30]
     );
+
+    // another asset pair sorting after the tiered group
+    let other_infos = [
+        raw_infos[0].clone(),
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+    ];
+    let other_pair = PairInfoRaw {
+        asset_infos: other_infos.clone(),
+        contract_addr: deps.api.addr_canonicalize("pair0001").unwrap(),
+        liquidity_token: deps.api.addr_canonicalize("liquidity0001").unwrap(),
+        ..default_pair.clone()
+    };
+    store_pair(deps.as_mut().storage, &pair_key(&other_infos), &other_pair).unwrap();
+
+    // every tier of an asset pair lands on the same page
+    let mut pages = vec![];
+    let mut start_after = None;
+    loop {
+        let res: PairsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Pairs {
+                    start_after: start_after.clone(),
+                    limit: Some(1),
+                    with_metadata: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match res.pairs.last() {
+            Some(last) => start_after = Some(last.asset_infos.clone()),
+            None => break,
+        }
+        pages.push(res.pairs.len());
+    }
+    pages.sort_unstable();
+    assert_eq!(pages, vec![1, 2]);
 }
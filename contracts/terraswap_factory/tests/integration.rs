@@ -0,0 +1,274 @@
+use cosmwasm_std::{
+    Addr, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use terraswap::asset::{Asset, AssetInfo, PairInfo};
+use terraswap::factory::{
+    ExecuteMsg, InstantiateMsg, OutdatedPairsResponse, PairsResponse, QueryMsg,
+};
+use terraswap::pair::{ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg};
+use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+
+const OWNER: &str = "owner0000";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_token::contract::execute,
+        terraswap_token::contract::instantiate,
+        terraswap_token::contract::query,
+    ))
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_factory::contract::execute,
+            terraswap_factory::contract::instantiate,
+            terraswap_factory::contract::query,
+        )
+        .with_reply(terraswap_factory::contract::reply),
+    )
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_pair::contract::execute,
+            terraswap_pair::contract::instantiate,
+            terraswap_pair::contract::query,
+        )
+        .with_reply(terraswap_pair::contract::reply)
+        .with_migrate(terraswap_pair::contract::migrate),
+    )
+}
+
+/// Leaves the pair in the state of the deployed version, without the factory stored
+fn legacy_pair_instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: PairInstantiateMsg,
+) -> StdResult<Response> {
+    let res = terraswap_pair::contract::instantiate(deps.branch(), env, info, msg)?;
+    terraswap_pair::state::FACTORY.remove(deps.storage);
+    set_contract_version(deps.storage, "crates.io:terraswap-pair", "0.2.0")?;
+
+    Ok(res)
+}
+
+fn legacy_pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_pair::contract::execute,
+            legacy_pair_instantiate,
+            terraswap_pair::contract::query,
+        )
+        .with_reply(terraswap_pair::contract::reply),
+    )
+}
+
+fn query_outdated_pairs(
+    app: &App,
+    factory: &Addr,
+    start_after: Option<[AssetInfo; 2]>,
+) -> OutdatedPairsResponse {
+    app.wrap()
+        .query_wasm_smart(
+            factory,
+            &QueryMsg::OutdatedPairs {
+                start_after,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+fn provide_liquidity(app: &mut App, pair: &PairInfo) -> StdResult<()> {
+    let assets = [0, 1].map(|i| Asset {
+        info: pair.asset_infos[i].clone(),
+        amount: Uint128::from(1_000_000u128),
+    });
+    for asset in assets.iter() {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            Addr::unchecked(asset.info.to_string()),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair.contract_addr.clone(),
+                amount: asset.amount,
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        Addr::unchecked(&pair.contract_addr),
+        &PairExecuteMsg::ProvideLiquidity {
+            assets,
+            receiver: None,
+            deadline: None,
+            slippage_tolerance: None,
+        },
+        &[],
+    )
+    .map_err(|err| StdError::generic_err(err.root_cause().to_string()))?;
+
+    Ok(())
+}
+
+#[test]
+fn migrate_deployed_pairs() {
+    let mut app = App::default();
+
+    let token_code_id = app.store_code(token_contract());
+    let legacy_pair_code_id = app.store_code(legacy_pair_contract());
+    let pair_code_id = app.store_code(pair_contract());
+    let factory_code_id = app.store_code(factory_contract());
+
+    let tokens = ["TKNA", "TKNB"].map(|symbol| {
+        app.instantiate_contract(
+            token_code_id,
+            Addr::unchecked(OWNER),
+            &TokenInstantiateMsg {
+                name: "terraswap token".to_string(),
+                symbol: symbol.to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: OWNER.to_string(),
+                    amount: Uint128::from(1_000_000_000u128),
+                }],
+                mint: None,
+            },
+            &[],
+            symbol,
+            None,
+        )
+        .unwrap()
+    });
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                pair_code_id: legacy_pair_code_id,
+                token_code_id,
+                timelock_delay: 0,
+            },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    for fee_tier in [5u16, 30u16] {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            factory.clone(),
+            &ExecuteMsg::CreatePair {
+                assets: tokens.clone().map(|token| Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token.to_string(),
+                    },
+                    amount: Uint128::zero(),
+                }),
+                fee_tier: Some(fee_tier),
+                label: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let res: PairsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &factory,
+            &QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+                with_metadata: None,
+            },
+        )
+        .unwrap();
+    let pairs = res.pairs;
+    assert_eq!(pairs.len(), 2);
+
+    // the deployed pairs cannot read the pause of their factory
+    let err = provide_liquidity(&mut app, &pairs[0]).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("cosmwasm_std::addresses::CanonicalAddr not found")
+    );
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            pair_code_id: Some(pair_code_id),
+            max_referral_commission: None,
+            timelock_delay: None,
+            creation_fee: None,
+            fee_recipient: None,
+            permissionless: None,
+            restrict_assets: None,
+            guardian: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &ExecuteMsg::ExecuteAction { id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(query_outdated_pairs(&app, &factory, None).pairs, pairs);
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &ExecuteMsg::MigratePairs {
+            start_after: None,
+            limit: None,
+            code_id: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &ExecuteMsg::ExecuteAction { id: 2 },
+        &[],
+    )
+    .unwrap();
+
+    let res = query_outdated_pairs(&app, &factory, None);
+    assert_eq!(res.pairs, vec![]);
+    assert_eq!(
+        query_outdated_pairs(&app, &factory, res.last_scanned),
+        OutdatedPairsResponse {
+            pairs: vec![],
+            last_scanned: None,
+        }
+    );
+
+    for pair in pairs.iter() {
+        assert_eq!(
+            app.wrap()
+                .query_wasm_contract_info(&pair.contract_addr)
+                .unwrap()
+                .code_id,
+            pair_code_id
+        );
+        provide_liquidity(&mut app, pair).unwrap();
+    }
+}
@@ -0,0 +1,463 @@
+use crate::error::ContractError;
+use crate::state::{read_routes, BeneficiaryRaw, Config, Route, CONFIG, ROUTES};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    coins, to_binary, Api, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+
+use cw2::set_contract_version;
+use cw20::Cw20ExecuteMsg;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::fee_collector::{
+    Beneficiary, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RouteResponse,
+    RoutesResponse,
+};
+use terraswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+use terraswap::util::migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:terraswap-fee-collector";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    assert_max_spread(msg.max_spread)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            terraswap_router: deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(&msg.terraswap_router)?.as_str())?,
+            target_asset_info: msg.target_asset_info.to_raw(deps.api)?,
+            beneficiaries: beneficiaries_to_raw(deps.api, msg.beneficiaries)?,
+            max_spread: msg.max_spread,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Collect { assets } => collect(deps, env, assets),
+        ExecuteMsg::UpdateRoute {
+            asset_info,
+            operations,
+            belief_price,
+        } => update_route(deps, info, asset_info, operations, belief_price),
+        ExecuteMsg::RemoveRoute { asset_info } => remove_route(deps, info, asset_info),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            beneficiaries,
+            max_spread,
+        } => update_config(deps, info, owner, beneficiaries, max_spread),
+        ExecuteMsg::Distribute {} => distribute(deps, env, info),
+    }
+}
+
+fn beneficiaries_to_raw(
+    api: &dyn Api,
+    beneficiaries: Vec<Beneficiary>,
+) -> Result<Vec<BeneficiaryRaw>, ContractError> {
+    if beneficiaries.is_empty() {
+        return Err(ContractError::NoBeneficiaries {});
+    }
+
+    beneficiaries
+        .into_iter()
+        .map(|beneficiary| {
+            if beneficiary.weight == 0 {
+                return Err(ContractError::InvalidZeroWeight {});
+            }
+
+            Ok(BeneficiaryRaw {
+                address: api
+                    .addr_canonicalize(api.addr_validate(&beneficiary.address)?.as_str())?,
+                weight: beneficiary.weight,
+            })
+        })
+        .collect()
+}
+
+fn assert_max_spread(max_spread: Decimal) -> Result<(), ContractError> {
+    if max_spread >= Decimal::one() {
+        return Err(ContractError::InvalidMaxSpread {});
+    }
+
+    Ok(())
+}
+
+// Anyone can execute it to convert the collected assets
+pub fn collect(deps: DepsMut, env: Env, assets: Vec<AssetInfo>) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let terraswap_router = deps.api.addr_humanize(&config.terraswap_router)?;
+    let target_asset_info = config.target_asset_info.to_normal(deps.api)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut collected: Vec<Asset> = vec![];
+    for asset_info in assets {
+        // the target asset is distributed as it is
+        if asset_info == target_asset_info || collected.iter().any(|a| a.info == asset_info) {
+            continue;
+        }
+
+        let route = ROUTES
+            .may_load(deps.storage, asset_info.to_raw(deps.api)?.as_bytes())?
+            .ok_or_else(|| ContractError::RouteNotFound {
+                asset_info: asset_info.to_string(),
+            })?;
+
+        let amount =
+            asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        // the router reverts the swap when it returns less than
+        // `amount / belief_price * (1 - max_spread)`
+        let expected_return =
+            amount.multiply_ratio(Decimal::one().atomics(), route.belief_price.atomics());
+        let minimum_receive = Some(expected_return * (Decimal::one() - config.max_spread));
+
+        messages.push(match &asset_info {
+            AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: terraswap_router.to_string(),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: route.operations,
+                    minimum_receive,
+                    to: None,
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })?,
+                funds: coins(amount.u128(), denom),
+            }),
+            AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: terraswap_router.to_string(),
+                    amount,
+                    msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: route.operations,
+                        minimum_receive,
+                        to: None,
+                        deadline: None,
+                        referral_address: None,
+                        referral_commission: None,
+                    })?,
+                })?,
+                funds: vec![],
+            }),
+        });
+
+        collected.push(Asset {
+            info: asset_info,
+            amount,
+        });
+    }
+
+    // distribute after all swaps are executed
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::Distribute {})?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "collect"),
+        (
+            "collected",
+            &collected
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+        ),
+    ]))
+}
+
+// Only the contract itself can execute it
+pub fn distribute(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let target_asset_info = config.target_asset_info.to_normal(deps.api)?;
+    let balance =
+        target_asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+
+    let total_weight: u64 = config.beneficiaries.iter().map(|b| b.weight).sum();
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut distributed = Uint128::zero();
+    for beneficiary in config.beneficiaries {
+        // the remainder is kept for the next distribution
+        let amount = balance.multiply_ratio(beneficiary.weight, total_weight);
+        if amount.is_zero() {
+            continue;
+        }
+
+        distributed = distributed.checked_add(amount)?;
+        messages.push(
+            Asset {
+                info: target_asset_info.clone(),
+                amount,
+            }
+            .into_msg(deps.api.addr_humanize(&beneficiary.address)?)?,
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "distribute"),
+        (
+            "distributed",
+            &Asset {
+                info: target_asset_info,
+                amount: distributed,
+            }
+            .to_string(),
+        ),
+    ]))
+}
+
+// Only owner can execute it
+pub fn update_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    operations: Vec<SwapOperation>,
+    belief_price: Decimal,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let target_asset_info = config.target_asset_info.to_normal(deps.api)?;
+    assert_route(&asset_info, &target_asset_info, &operations)?;
+
+    if belief_price.is_zero() {
+        return Err(ContractError::InvalidZeroBeliefPrice {});
+    }
+
+    let raw_info = asset_info.to_raw(deps.api)?;
+    ROUTES.save(
+        deps.storage,
+        raw_info.as_bytes(),
+        &Route {
+            asset_info: raw_info.clone(),
+            operations,
+            belief_price,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_route"),
+        ("asset_info", &asset_info.to_string()),
+    ]))
+}
+
+/// The route must start from `asset_info`, end with the target asset
+/// and every operation must offer the asset asked by the previous one
+fn assert_route(
+    asset_info: &AssetInfo,
+    target_asset_info: &AssetInfo,
+    operations: &[SwapOperation],
+) -> Result<(), ContractError> {
+    if operations.is_empty() {
+        return Err(ContractError::NoSwapOperations {});
+    }
+
+    if asset_info == target_asset_info {
+        return Err(ContractError::InvalidRoute {
+            reason: "the target asset needs no route".to_string(),
+        });
+    }
+
+    let mut prev_asset_info = asset_info.clone();
+    for operation in operations {
+        match operation {
+            SwapOperation::TerraSwap {
+                offer_asset_info,
+                ask_asset_info,
+                ..
+            } => {
+                if *offer_asset_info != prev_asset_info {
+                    return Err(ContractError::InvalidRoute {
+                        reason: format!("expected offer asset {}", prev_asset_info),
+                    });
+                }
+
+                prev_asset_info = ask_asset_info.clone();
+            }
+        }
+    }
+
+    if prev_asset_info != *target_asset_info {
+        return Err(ContractError::InvalidRoute {
+            reason: format!("must end with the target asset {}", target_asset_info),
+        });
+    }
+
+    Ok(())
+}
+
+// Only owner can execute it
+pub fn remove_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ROUTES.remove(deps.storage, asset_info.to_raw(deps.api)?.as_bytes());
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_route"),
+        ("asset_info", &asset_info.to_string()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    beneficiaries: Option<Vec<Beneficiary>>,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(beneficiaries) = beneficiaries {
+        config.beneficiaries = beneficiaries_to_raw(deps.api, beneficiaries)?;
+    }
+
+    if let Some(max_spread) = max_spread {
+        assert_max_spread(max_spread)?;
+        config.max_spread = max_spread;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Route { asset_info } => to_binary(&query_route(deps, asset_info)?),
+        QueryMsg::Routes { start_after, limit } => {
+            to_binary(&query_routes(deps, start_after, limit)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let resp = ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        terraswap_router: deps
+            .api
+            .addr_humanize(&config.terraswap_router)?
+            .to_string(),
+        target_asset_info: config.target_asset_info.to_normal(deps.api)?,
+        beneficiaries: config
+            .beneficiaries
+            .iter()
+            .map(|b| {
+                Ok(Beneficiary {
+                    address: deps.api.addr_humanize(&b.address)?.to_string(),
+                    weight: b.weight,
+                })
+            })
+            .collect::<StdResult<Vec<Beneficiary>>>()?,
+        max_spread: config.max_spread,
+    };
+
+    Ok(resp)
+}
+
+pub fn query_route(deps: Deps, asset_info: AssetInfo) -> StdResult<RouteResponse> {
+    let route = ROUTES.load(deps.storage, asset_info.to_raw(deps.api)?.as_bytes())?;
+
+    Ok(RouteResponse {
+        asset_info,
+        operations: route.operations,
+        belief_price: route.belief_price,
+    })
+}
+
+pub fn query_routes(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<RoutesResponse> {
+    let start_after = start_after.map(|a| a.to_raw(deps.api)).transpose()?;
+
+    let routes = read_routes(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|route| {
+            Ok(RouteResponse {
+                asset_info: route.asset_info.to_normal(deps.api)?,
+                operations: route.operations,
+                belief_price: route.belief_price,
+            })
+        })
+        .collect::<StdResult<Vec<RouteResponse>>>()?;
+
+    Ok(RoutesResponse { routes })
+}
+
+const TARGET_CONTRACT_VERSION: &str = "0.1.0";
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    migrate_version(
+        deps,
+        TARGET_CONTRACT_VERSION,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+
+    Ok(Response::default())
+}
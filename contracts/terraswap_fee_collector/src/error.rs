@@ -0,0 +1,35 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Must provide at least one beneficiary")]
+    NoBeneficiaries {},
+
+    #[error("Beneficiary weight must be greater than zero")]
+    InvalidZeroWeight {},
+
+    #[error("Must provide swap operations")]
+    NoSwapOperations {},
+
+    #[error("Invalid route: {reason}")]
+    InvalidRoute { reason: String },
+
+    #[error("No route registered for {asset_info}")]
+    RouteNotFound { asset_info: String },
+
+    #[error("Belief price must be greater than zero")]
+    InvalidZeroBeliefPrice {},
+
+    #[error("Max spread must be less than one")]
+    InvalidMaxSpread {},
+}
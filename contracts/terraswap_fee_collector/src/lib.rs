@@ -0,0 +1,7 @@
+pub mod contract;
+pub mod state;
+
+mod error;
+
+#[cfg(test)]
+mod testing;
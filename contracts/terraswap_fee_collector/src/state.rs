@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
+use terraswap::asset::AssetInfoRaw;
+use terraswap::router::SwapOperation;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub terraswap_router: CanonicalAddr,
+    pub target_asset_info: AssetInfoRaw,
+    pub beneficiaries: Vec<BeneficiaryRaw>,
+    pub max_spread: Decimal,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct BeneficiaryRaw {
+    pub address: CanonicalAddr,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Route {
+    pub asset_info: AssetInfoRaw,
+    pub operations: Vec<SwapOperation>,
+    pub belief_price: Decimal,
+}
+
+// key : offer asset info / value: router operations to the target asset
+pub const ROUTES: Map<&[u8], Route> = Map::new("routes");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_routes(
+    storage: &dyn Storage,
+    start_after: Option<AssetInfoRaw>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Route>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|info| Bound::ExclusiveRaw(info.as_bytes().to_vec()));
+
+    ROUTES
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(v)
+        })
+        .collect()
+}
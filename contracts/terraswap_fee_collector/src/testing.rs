@@ -0,0 +1,430 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use terraswap::mock_querier::mock_dependencies;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use terraswap::asset::AssetInfo;
+use terraswap::fee_collector::{
+    Beneficiary, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RouteResponse,
+    RoutesResponse,
+};
+use terraswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.to_string(),
+    }
+}
+
+fn swap(offer_asset_info: AssetInfo, ask_asset_info: AssetInfo) -> SwapOperation {
+    SwapOperation::TerraSwap {
+        offer_asset_info,
+        ask_asset_info,
+        fee_tier: None,
+    }
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        terraswap_router: "router0000".to_string(),
+        target_asset_info: native("uusd"),
+        beneficiaries: vec![
+            Beneficiary {
+                address: "addr0000".to_string(),
+                weight: 3,
+            },
+            Beneficiary {
+                address: "addr0001".to_string(),
+                weight: 1,
+            },
+        ],
+        max_spread: Decimal::percent(1),
+    }
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            terraswap_router: "router0000".to_string(),
+            target_asset_info: native("uusd"),
+            beneficiaries: instantiate_msg().beneficiaries,
+            max_spread: Decimal::percent(1),
+        }
+    );
+
+    // beneficiaries are required
+    let mut msg = instantiate_msg();
+    msg.beneficiaries = vec![];
+    let info = mock_info("owner0000", &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::NoBeneficiaries {}));
+
+    // zero weight is not allowed
+    let mut msg = instantiate_msg();
+    msg.beneficiaries[1].weight = 0;
+    let info = mock_info("owner0000", &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidZeroWeight {}));
+}
+
+#[test]
+fn update_route() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    let operations = vec![
+        swap(token("asset0000"), native("uluna")),
+        swap(native("uluna"), native("uusd")),
+    ];
+
+    // unauthorized
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: operations.clone(),
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // must start from the asset
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: vec![swap(native("uluna"), native("uusd"))],
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidRoute {
+            reason: "expected offer asset asset0000".to_string()
+        })
+    );
+
+    // must end with the target asset
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: vec![swap(token("asset0000"), native("uluna"))],
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidRoute {
+            reason: "must end with the target asset uusd".to_string()
+        })
+    );
+
+    // empty operations
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: vec![],
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::NoSwapOperations {}));
+
+    // zero belief price
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: operations.clone(),
+        belief_price: Decimal::zero(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidZeroBeliefPrice {}));
+
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: operations.clone(),
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_route"),
+            attr("asset_info", "asset0000")
+        ]
+    );
+
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: native("uluna"),
+        operations: vec![swap(native("uluna"), native("uusd"))],
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let route: RouteResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Route {
+                asset_info: token("asset0000"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        route,
+        RouteResponse {
+            asset_info: token("asset0000"),
+            operations,
+            belief_price: Decimal::one(),
+        }
+    );
+
+    let routes: RoutesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Routes {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(routes.routes.len(), 2);
+
+    let msg = ExecuteMsg::RemoveRoute {
+        asset_info: native("uluna"),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let routes: RoutesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Routes {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(routes.routes.len(), 1);
+    assert_eq!(routes.routes[0].asset_info, token("asset0000"));
+}
+
+#[test]
+fn collect() {
+    let mut deps = mock_dependencies(&[coin(100, "uluna"), coin(1000, "uusd")]);
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(200u128))],
+    )]);
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    let token_operations = vec![
+        swap(token("asset0000"), native("uluna")),
+        swap(native("uluna"), native("uusd")),
+    ];
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: token("asset0000"),
+        operations: token_operations.clone(),
+        belief_price: Decimal::percent(200),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // no route registered for uluna
+    let msg = ExecuteMsg::Collect {
+        assets: vec![token("asset0000"), native("uluna")],
+    };
+    let info = mock_info("anyone", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(
+        res,
+        Err(ContractError::RouteNotFound {
+            asset_info: "uluna".to_string()
+        })
+    );
+
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: native("uluna"),
+        operations: vec![swap(native("uluna"), native("uusd"))],
+        belief_price: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the target asset and the duplicated assets are skipped
+    let msg = ExecuteMsg::Collect {
+        assets: vec![
+            token("asset0000"),
+            native("uluna"),
+            native("uusd"),
+            native("uluna"),
+        ],
+    };
+    let info = mock_info("anyone", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "router0000".to_string(),
+                    amount: Uint128::from(200u128),
+                    msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: token_operations,
+                        minimum_receive: Some(Uint128::from(99u128)),
+                        to: None,
+                        deadline: None,
+                        referral_address: None,
+                        referral_commission: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "router0000".to_string(),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![swap(native("uluna"), native("uusd"))],
+                    minimum_receive: Some(Uint128::from(99u128)),
+                    to: None,
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })
+                .unwrap(),
+                funds: coins(100, "uluna"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Distribute {}).unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "collect"),
+            attr("collected", "200asset0000, 100uluna"),
+        ]
+    );
+}
+
+#[test]
+fn distribute() {
+    let mut deps = mock_dependencies(&[coin(1001, "uusd")]);
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    // only the contract itself can distribute
+    let info = mock_info("anyone", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Distribute {});
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Distribute {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(750, "uusd"),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: coins(250, "uusd"),
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute"),
+            attr("distributed", "1000uusd")
+        ]
+    );
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    // unauthorized
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("addr0000".to_string()),
+        beneficiaries: None,
+        max_spread: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let beneficiaries = vec![Beneficiary {
+        address: "addr0002".to_string(),
+        weight: 1,
+    }];
+
+    // max spread must be less than one
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        beneficiaries: None,
+        max_spread: Some(Decimal::one()),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidMaxSpread {}));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("addr0000".to_string()),
+        beneficiaries: Some(beneficiaries.clone()),
+        max_spread: Some(Decimal::percent(2)),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.owner, "addr0000".to_string());
+    assert_eq!(config.beneficiaries, beneficiaries);
+    assert_eq!(config.max_spread, Decimal::percent(2));
+}
@@ -0,0 +1,297 @@
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::factory::{
+    ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg,
+};
+use terraswap::fee_collector::{Beneficiary, ExecuteMsg, InstantiateMsg};
+use terraswap::router::{InstantiateMsg as RouterInstantiateMsg, SwapOperation};
+use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+
+const OWNER: &str = "owner0000";
+const BENEFICIARY_0: &str = "addr0000";
+const BENEFICIARY_1: &str = "addr0001";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_token::contract::execute,
+        terraswap_token::contract::instantiate,
+        terraswap_token::contract::query,
+    ))
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_pair::contract::execute,
+            terraswap_pair::contract::instantiate,
+            terraswap_pair::contract::query,
+        )
+        .with_reply(terraswap_pair::contract::reply),
+    )
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_factory::contract::execute,
+            terraswap_factory::contract::instantiate,
+            terraswap_factory::contract::query,
+        )
+        .with_reply(terraswap_factory::contract::reply),
+    )
+}
+
+fn router_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_router::contract::execute,
+        terraswap_router::contract::instantiate,
+        terraswap_router::contract::query,
+    ))
+}
+
+fn fee_collector_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_fee_collector::contract::execute,
+        terraswap_fee_collector::contract::instantiate,
+        terraswap_fee_collector::contract::query,
+    ))
+}
+
+fn query_token_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    res.balance
+}
+
+fn instantiate_token(app: &mut App, code_id: u64, symbol: &str) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &TokenInstantiateMsg {
+            name: "terraswap token".to_string(),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: OWNER.to_string(),
+                amount: Uint128::from(1_000_000_000u128),
+            }],
+            mint: None,
+        },
+        &[],
+        "token",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn collect_and_distribute_through_router() {
+    let mut app = App::default();
+
+    let token_code_id = app.store_code(token_contract());
+    let pair_code_id = app.store_code(pair_contract());
+    let factory_code_id = app.store_code(factory_contract());
+    let router_code_id = app.store_code(router_contract());
+    let fee_collector_code_id = app.store_code(fee_collector_contract());
+
+    let fee_token = instantiate_token(&mut app, token_code_id, "FEE");
+    let target_token = instantiate_token(&mut app, token_code_id, "TGT");
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(OWNER),
+            &FactoryInstantiateMsg {
+                pair_code_id,
+                token_code_id,
+                timelock_delay: 0,
+            },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    let router = app
+        .instantiate_contract(
+            router_code_id,
+            Addr::unchecked(OWNER),
+            &RouterInstantiateMsg {
+                terraswap_factory: factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let fee_asset_info = AssetInfo::Token {
+        contract_addr: fee_token.to_string(),
+    };
+    let target_asset_info = AssetInfo::Token {
+        contract_addr: target_token.to_string(),
+    };
+
+    // create the pair with 1:1 initial liquidity
+    for token in [&fee_token, &target_token] {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: factory.to_string(),
+                amount: Uint128::from(100_000_000u128),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory,
+        &FactoryExecuteMsg::CreatePair {
+            assets: [
+                Asset {
+                    info: fee_asset_info.clone(),
+                    amount: Uint128::from(100_000_000u128),
+                },
+                Asset {
+                    info: target_asset_info.clone(),
+                    amount: Uint128::from(100_000_000u128),
+                },
+            ],
+            label: None,
+            fee_tier: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let fee_collector = app
+        .instantiate_contract(
+            fee_collector_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                terraswap_router: router.to_string(),
+                target_asset_info: target_asset_info.clone(),
+                beneficiaries: vec![
+                    Beneficiary {
+                        address: BENEFICIARY_0.to_string(),
+                        weight: 1,
+                    },
+                    Beneficiary {
+                        address: BENEFICIARY_1.to_string(),
+                        weight: 1,
+                    },
+                ],
+                max_spread: Decimal::percent(2),
+            },
+            &[],
+            "fee collector",
+            None,
+        )
+        .unwrap();
+
+    // the owner expects 1.05 target per fee token
+    let operations = vec![SwapOperation::TerraSwap {
+        offer_asset_info: fee_asset_info.clone(),
+        ask_asset_info: target_asset_info,
+        fee_tier: None,
+    }];
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        fee_collector.clone(),
+        &ExecuteMsg::UpdateRoute {
+            asset_info: fee_asset_info.clone(),
+            operations: operations.clone(),
+            belief_price: Decimal::from_ratio(100u128, 105u128),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the fees are collected
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        fee_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: fee_collector.to_string(),
+            amount: Uint128::from(1_000_000u128),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the swap returns 987_128, less than 1_050_000 * 0.98
+    let err = app
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            fee_collector.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![fee_asset_info.clone()],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: assertion failed; minimum receive amount: 1029000, swap amount: 987128"
+    );
+
+    assert_eq!(
+        query_token_balance(&app, &fee_token, fee_collector.as_str()),
+        Uint128::from(1_000_000u128)
+    );
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        fee_collector.clone(),
+        &ExecuteMsg::UpdateRoute {
+            asset_info: fee_asset_info.clone(),
+            operations,
+            belief_price: Decimal::one(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // anyone can trigger the collection
+    app.execute_contract(
+        Addr::unchecked("anyone"),
+        fee_collector.clone(),
+        &ExecuteMsg::Collect {
+            assets: vec![fee_asset_info],
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_token_balance(&app, &fee_token, fee_collector.as_str()),
+        Uint128::zero()
+    );
+
+    // the swapped 987_128 is split by the weights
+    assert_eq!(
+        query_token_balance(&app, &target_token, BENEFICIARY_0),
+        Uint128::from(493_564u128)
+    );
+    assert_eq!(
+        query_token_balance(&app, &target_token, BENEFICIARY_1),
+        Uint128::from(493_564u128)
+    );
+    assert_eq!(
+        query_token_balance(&app, &target_token, fee_collector.as_str()),
+        Uint128::zero()
+    );
+}
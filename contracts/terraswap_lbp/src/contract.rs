@@ -0,0 +1,535 @@
+use crate::error::ContractError;
+use crate::math::{compute_offer_amount, compute_swap};
+use crate::state::{Config, CONFIG};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Response, StdResult, Uint128, Uint256, WasmMsg,
+};
+
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use std::str::FromStr;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::lbp::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
+    WeightsResponse,
+};
+use terraswap::pair::{ReverseSimulationResponse, SimulationResponse};
+use terraswap::util::migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:terraswap-lbp";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.asset_infos[0].equal(&msg.asset_infos[1]) {
+        return Err(ContractError::SameAssets {});
+    }
+
+    if msg
+        .start_weights
+        .iter()
+        .chain(msg.end_weights.iter())
+        .any(|weight| weight.is_zero())
+    {
+        return Err(ContractError::InvalidZeroWeight {});
+    }
+
+    if msg.start_time >= msg.end_time {
+        return Err(ContractError::InvalidTimes {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(&msg.owner)?.as_str())?,
+            asset_infos: [
+                msg.asset_infos[0].to_raw(deps.api)?,
+                msg.asset_infos[1].to_raw(deps.api)?,
+            ],
+            start_weights: msg.start_weights,
+            end_weights: msg.end_weights,
+            start_time: msg.start_time,
+            end_time: msg.end_time,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProvideLiquidity { assets } => provide_liquidity(deps, env, info, assets),
+        ExecuteMsg::WithdrawLiquidity {} => withdraw_liquidity(deps, env, info),
+        ExecuteMsg::Swap {
+            offer_asset,
+            belief_price,
+            max_spread,
+            to,
+            deadline,
+        } => {
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            swap(
+                deps,
+                env,
+                info.sender,
+                offer_asset,
+                belief_price,
+                max_spread,
+                to_addr,
+                deadline,
+            )
+        }
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Swap {
+            belief_price,
+            max_spread,
+            to,
+            deadline,
+        } => {
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            // the asset contract is checked against the pool assets in the swap
+            swap(
+                deps,
+                env,
+                Addr::unchecked(cw20_msg.sender),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender.to_string(),
+                    },
+                    amount: cw20_msg.amount,
+                },
+                belief_price,
+                max_spread,
+                to_addr,
+                deadline,
+            )
+        }
+    }
+}
+
+pub fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: [Asset; 2],
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if assets[0].info.equal(&assets[1].info) {
+        return Err(ContractError::AssetMismatch {});
+    }
+
+    let asset_infos = [
+        config.asset_infos[0].to_normal(deps.api)?,
+        config.asset_infos[1].to_normal(deps.api)?,
+    ];
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for asset in assets.iter() {
+        if !asset_infos.iter().any(|info| info.equal(&asset.info)) {
+            return Err(ContractError::AssetMismatch {});
+        }
+
+        asset.assert_sent_native_token_balance(&info)?;
+        if let AssetInfo::Token { contract_addr } = &asset.info {
+            if !asset.amount.is_zero() {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: asset.amount,
+                    })?,
+                    funds: vec![],
+                }));
+            }
+        }
+    }
+
+    if assets.iter().all(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "provide_liquidity"),
+        ("sender", info.sender.as_str()),
+        ("assets", &format!("{}, {}", assets[0], assets[1])),
+    ]))
+}
+
+/// The whole pool is returned to the owner, which is only allowed
+/// before the sale starts or after it ends
+pub fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let now = env.block.time.seconds();
+    if now >= config.start_time && now < config.end_time {
+        return Err(ContractError::SaleInProgress {
+            end_time: config.end_time,
+        });
+    }
+
+    let pools = query_pools(deps.as_ref(), &env, &config)?;
+    if pools.iter().all(|pool| pool.amount.is_zero()) {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for pool in pools.iter() {
+        if !pool.amount.is_zero() {
+            messages.push(pool.clone().into_msg(info.sender.clone())?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw_liquidity"),
+        ("sender", info.sender.as_str()),
+        ("refund_assets", &format!("{}, {}", pools[0], pools[1])),
+    ]))
+}
+
+// CONTRACT - a user must do token approval
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+    deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    assert_deadline(env.block.time.seconds(), deadline)?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    if env.block.time.seconds() < config.start_time {
+        return Err(ContractError::SwapNotStarted {
+            start_time: config.start_time,
+        });
+    }
+
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut pools = query_pools(deps.as_ref(), &env, &config)?;
+    let weights = config.weights(env.block.time.seconds());
+
+    let (offer_index, ask_index) = if offer_asset.info.equal(&pools[0].info) {
+        (0, 1)
+    } else if offer_asset.info.equal(&pools[1].info) {
+        (1, 0)
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    // If the asset balance is already increased
+    // To calculated properly we should subtract user deposit from the pool
+    pools[offer_index].amount = pools[offer_index].amount.checked_sub(offer_asset.amount)?;
+
+    let offer_pool = pools[offer_index].clone();
+    let ask_pool = pools[ask_index].clone();
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        weights[offer_index],
+        ask_pool.amount,
+        weights[ask_index],
+        offer_asset.amount,
+    )?;
+
+    let return_asset = Asset {
+        info: ask_pool.info.clone(),
+        amount: return_amount,
+    };
+
+    // check max spread limit if exist
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        offer_asset.amount,
+        return_amount,
+        spread_amount,
+    )?;
+
+    let receiver = to.unwrap_or_else(|| sender.clone());
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !return_amount.is_zero() {
+        messages.push(return_asset.into_msg(receiver.clone())?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "swap"),
+        ("sender", sender.as_str()),
+        ("receiver", receiver.as_str()),
+        ("offer_asset", &offer_asset.info.to_string()),
+        ("ask_asset", &ask_pool.info.to_string()),
+        ("offer_amount", &offer_asset.amount.to_string()),
+        ("return_amount", &return_amount.to_string()),
+        ("spread_amount", &spread_amount.to_string()),
+        ("commission_amount", &commission_amount.to_string()),
+        ("offer_weight", &weights[offer_index].to_string()),
+        ("ask_weight", &weights[ask_index].to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps, env)?)?),
+        QueryMsg::Weights {} => Ok(to_binary(&query_weights(deps, env)?)?),
+        QueryMsg::Simulation { offer_asset } => {
+            Ok(to_binary(&query_simulation(deps, env, offer_asset)?)?)
+        }
+        QueryMsg::ReverseSimulation { ask_asset } => {
+            Ok(to_binary(&query_reverse_simulation(deps, env, ask_asset)?)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        asset_infos: [
+            config.asset_infos[0].to_normal(deps.api)?,
+            config.asset_infos[1].to_normal(deps.api)?,
+        ],
+        start_weights: config.start_weights,
+        end_weights: config.end_weights,
+        start_time: config.start_time,
+        end_time: config.end_time,
+    })
+}
+
+pub fn query_pool(deps: Deps, env: Env) -> Result<PoolResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(PoolResponse {
+        assets: query_pools(deps, &env, &config)?,
+    })
+}
+
+pub fn query_weights(deps: Deps, env: Env) -> Result<WeightsResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    Ok(WeightsResponse {
+        weights: config.weights(env.block.time.seconds()),
+    })
+}
+
+pub fn query_simulation(
+    deps: Deps,
+    env: Env,
+    offer_asset: Asset,
+) -> Result<SimulationResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pools = query_pools(deps, &env, &config)?;
+    let weights = config.weights(env.block.time.seconds());
+
+    let (offer_index, ask_index) = if offer_asset.info.equal(&pools[0].info) {
+        (0, 1)
+    } else if offer_asset.info.equal(&pools[1].info) {
+        (1, 0)
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        pools[offer_index].amount,
+        weights[offer_index],
+        pools[ask_index].amount,
+        weights[ask_index],
+        offer_asset.amount,
+    )?;
+
+    Ok(SimulationResponse {
+        return_amount,
+        spread_amount,
+        commission_amount,
+        referral_amount: Uint128::zero(),
+    })
+}
+
+pub fn query_reverse_simulation(
+    deps: Deps,
+    env: Env,
+    ask_asset: Asset,
+) -> Result<ReverseSimulationResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pools = query_pools(deps, &env, &config)?;
+    let weights = config.weights(env.block.time.seconds());
+
+    let (offer_index, ask_index) = if ask_asset.info.equal(&pools[0].info) {
+        (1, 0)
+    } else if ask_asset.info.equal(&pools[1].info) {
+        (0, 1)
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
+        pools[offer_index].amount,
+        weights[offer_index],
+        pools[ask_index].amount,
+        weights[ask_index],
+        ask_asset.amount,
+    )?;
+
+    Ok(ReverseSimulationResponse {
+        offer_amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+fn query_pools(deps: Deps, env: &Env, config: &Config) -> StdResult<[Asset; 2]> {
+    let asset_infos = [
+        config.asset_infos[0].to_normal(deps.api)?,
+        config.asset_infos[1].to_normal(deps.api)?,
+    ];
+
+    Ok([
+        Asset {
+            amount: asset_infos[0].query_pool(
+                &deps.querier,
+                deps.api,
+                env.contract.address.clone(),
+            )?,
+            info: asset_infos[0].clone(),
+        },
+        Asset {
+            amount: asset_infos[1].query_pool(
+                &deps.querier,
+                deps.api,
+                env.contract.address.clone(),
+            )?,
+            info: asset_infos[1].clone(),
+        },
+    ])
+}
+
+/// If `belief_price` and `max_spread` both are given,
+/// we compute new spread else we just use the spread
+/// computed from the weighted product
+pub fn assert_max_spread(
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    spread_amount: Uint128,
+) -> Result<(), ContractError> {
+    let offer_amount: Uint256 = offer_amount.into();
+    let return_amount: Uint256 = return_amount.into();
+    let spread_amount: Uint256 = spread_amount.into();
+
+    if let (Some(max_spread), Some(belief_price)) = (max_spread, belief_price) {
+        let belief_price: Decimal256 = Decimal256::from_str(&belief_price.to_string())?;
+        let max_spread: Decimal256 = Decimal256::from_str(&max_spread.to_string())?;
+
+        let expected_return = offer_amount * (Decimal256::one() / belief_price);
+        let spread_amount = if expected_return > return_amount {
+            expected_return - return_amount
+        } else {
+            Uint256::zero()
+        };
+
+        if return_amount < expected_return
+            && Decimal256::from_ratio(spread_amount, expected_return) > max_spread
+        {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+    } else if let Some(max_spread) = max_spread {
+        let max_spread: Decimal256 = Decimal256::from_str(&max_spread.to_string())?;
+        if Decimal256::from_ratio(spread_amount, return_amount + spread_amount) > max_spread {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+    }
+
+    Ok(())
+}
+
+pub fn assert_deadline(blocktime: u64, deadline: Option<u64>) -> Result<(), ContractError> {
+    if let Some(deadline) = deadline {
+        if blocktime >= deadline {
+            return Err(ContractError::ExpiredDeadline {});
+        }
+    }
+
+    Ok(())
+}
+
+const TARGET_CONTRACT_VERSION: &str = "0.1.0";
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    migrate_version(
+        deps,
+        TARGET_CONTRACT_VERSION,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+
+    Ok(Response::default())
+}
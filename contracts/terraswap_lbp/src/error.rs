@@ -0,0 +1,53 @@
+use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("{0}")]
+    ConversionOverflowError(#[from] ConversionOverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Asset infos must be different")]
+    SameAssets {},
+
+    #[error("Weights must be greater than zero")]
+    InvalidZeroWeight {},
+
+    #[error("start_time must be less than end_time")]
+    InvalidTimes {},
+
+    #[error("Asset mismatch")]
+    AssetMismatch {},
+
+    #[error("Pool is empty")]
+    EmptyPool {},
+
+    #[error("Swaps are enabled from {start_time}")]
+    SwapNotStarted { start_time: u64 },
+
+    #[error("Liquidity cannot be withdrawn until {end_time}")]
+    SaleInProgress { end_time: u64 },
+
+    #[error("Swap amount exceeds the maximum ratio of the pool")]
+    MaxRatioExceeded {},
+
+    #[error("Power is out of range")]
+    PowOutOfRange {},
+
+    #[error("Max spread assertion")]
+    MaxSpreadAssertion {},
+
+    #[error("Expired deadline")]
+    ExpiredDeadline {},
+}
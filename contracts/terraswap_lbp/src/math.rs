@@ -0,0 +1,218 @@
+use crate::error::ContractError;
+
+use cosmwasm_std::{Decimal256, Uint128, Uint256};
+use std::convert::{TryFrom, TryInto};
+
+/// The swap commission is absorbed into the pool, like the xyk pair
+pub const COMMISSION_RATE: u64 = 3;
+
+/// A swap can offer at most half of the offer pool
+const MAX_IN_RATIO: u64 = 2;
+/// A reverse swap can ask for at most a third of the ask pool
+const MAX_OUT_RATIO: u64 = 3;
+
+/// The binomial series of `pow` is summed until its terms get below 1e-10
+const POW_PRECISION: u128 = 100_000_000u128;
+
+/// Weights move linearly from `start_weights` at `start_time`
+/// to `end_weights` at `end_time`
+pub fn compute_weights(
+    start_weights: [Decimal256; 2],
+    end_weights: [Decimal256; 2],
+    start_time: u64,
+    end_time: u64,
+    time: u64,
+) -> [Decimal256; 2] {
+    if time <= start_time {
+        return start_weights;
+    }
+
+    if time >= end_time {
+        return end_weights;
+    }
+
+    let elapsed = Decimal256::from_ratio(time - start_time, end_time - start_time);
+    let interpolate = |start: Decimal256, end: Decimal256| {
+        if end >= start {
+            start + (end - start) * elapsed
+        } else {
+            start - (start - end) * elapsed
+        }
+    };
+
+    [
+        interpolate(start_weights[0], end_weights[0]),
+        interpolate(start_weights[1], end_weights[1]),
+    ]
+}
+
+/// offer => ask
+/// return_amount = ask_pool * (1 - (offer_pool / (offer_pool + offer_amount)) ^ (offer_weight / ask_weight))
+pub fn compute_swap(
+    offer_pool: Uint128,
+    offer_weight: Decimal256,
+    ask_pool: Uint128,
+    ask_weight: Decimal256,
+    offer_amount: Uint128,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    if offer_amount > offer_pool.multiply_ratio(1u128, MAX_IN_RATIO) {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    let offer_pool: Uint256 = offer_pool.into();
+    let ask_pool: Uint256 = ask_pool.into();
+    let offer_amount: Uint256 = offer_amount.into();
+
+    let ratio = pow(
+        Decimal256::from_ratio(offer_pool, offer_pool + offer_amount),
+        offer_weight / ask_weight,
+    )?;
+    let return_amount: Uint256 = ask_pool * (Decimal256::one() - ratio);
+
+    // calculate spread & commission
+    let spot_price = Decimal256::from_ratio(ask_pool, offer_pool) * (offer_weight / ask_weight);
+    let before_spread_deduction: Uint256 = offer_amount * spot_price;
+    let spread_amount = if before_spread_deduction > return_amount {
+        before_spread_deduction - return_amount
+    } else {
+        Uint256::zero()
+    };
+
+    let commission_rate = Decimal256::permille(COMMISSION_RATE);
+    let mut commission_amount: Uint256 = return_amount * commission_rate;
+    if return_amount != (commission_amount * (Decimal256::one() / commission_rate)) {
+        commission_amount += Uint256::from(1u128);
+    }
+
+    // commission will be absorbed to pool
+    let return_amount: Uint256 = return_amount - commission_amount;
+    Ok((
+        return_amount.try_into()?,
+        spread_amount.try_into()?,
+        commission_amount.try_into()?,
+    ))
+}
+
+/// ask => offer
+/// offer_amount = offer_pool * ((ask_pool / (ask_pool - ask_amount / (1 - commission_rate))) ^ (ask_weight / offer_weight) - 1)
+pub fn compute_offer_amount(
+    offer_pool: Uint128,
+    offer_weight: Decimal256,
+    ask_pool: Uint128,
+    ask_weight: Decimal256,
+    ask_amount: Uint128,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    if offer_pool.is_zero() || ask_pool.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    let offer_pool: Uint256 = offer_pool.into();
+    let ask_pool: Uint256 = ask_pool.into();
+    let ask_amount: Uint256 = ask_amount.into();
+
+    let one_minus_commission = Decimal256::one() - Decimal256::permille(COMMISSION_RATE);
+    let inv_one_minus_commission = Decimal256::one() / one_minus_commission;
+    let mut before_commission_deduction: Uint256 = ask_amount * inv_one_minus_commission;
+    if before_commission_deduction * one_minus_commission != ask_amount {
+        before_commission_deduction += Uint256::from(1u8);
+    }
+
+    if before_commission_deduction > ask_pool / Uint256::from(MAX_OUT_RATIO) {
+        return Err(ContractError::MaxRatioExceeded {});
+    }
+
+    let ratio = pow(
+        Decimal256::from_ratio(ask_pool, ask_pool - before_commission_deduction),
+        ask_weight / offer_weight,
+    )?;
+    let offer_amount: Uint256 = offer_pool * (ratio - Decimal256::one()) + Uint256::from(1u8);
+
+    let spot_price = Decimal256::from_ratio(ask_pool, offer_pool) * (offer_weight / ask_weight);
+    let before_spread_deduction: Uint256 = offer_amount * spot_price;
+    let spread_amount = if before_spread_deduction > before_commission_deduction {
+        before_spread_deduction - before_commission_deduction
+    } else {
+        Uint256::zero()
+    };
+
+    let commission_amount = before_commission_deduction - ask_amount;
+
+    Ok((
+        offer_amount.try_into()?,
+        spread_amount.try_into()?,
+        commission_amount.try_into()?,
+    ))
+}
+
+/// base ^ exp for a base within (0, 2), the integer part of the exponent is
+/// raised exactly and the fractional part with a binomial series
+pub fn pow(base: Decimal256, exp: Decimal256) -> Result<Decimal256, ContractError> {
+    let two = Decimal256::from_ratio(2u8, 1u8);
+    if base.is_zero() || base >= two {
+        return Err(ContractError::PowOutOfRange {});
+    }
+
+    let whole: Uint256 = exp * Uint256::from(1u8);
+    let remain = exp - Decimal256::from_ratio(whole, 1u8);
+
+    let whole_exp: u32 = Uint128::try_from(whole)
+        .ok()
+        .and_then(|whole| whole.u128().try_into().ok())
+        .ok_or(ContractError::PowOutOfRange {})?;
+    let whole_pow = base.checked_pow(whole_exp)?;
+    if remain.is_zero() {
+        return Ok(whole_pow);
+    }
+
+    Ok(whole_pow.checked_mul(pow_approx(base, remain))?)
+}
+
+/// (1 + x) ^ a = 1 + a * x + a * (a - 1) * x^2 / 2! + ...
+/// signs are tracked separately as Decimal256 is unsigned
+fn pow_approx(base: Decimal256, exp: Decimal256) -> Decimal256 {
+    let precision = Decimal256::from_ratio(POW_PRECISION, 10u128.pow(18));
+    let (x, x_neg) = sub_sign(base, Decimal256::one());
+
+    let mut term = Decimal256::one();
+    let mut sum = Decimal256::one();
+    let mut negative = false;
+
+    let mut i = 1u64;
+    while term >= precision {
+        let big_k = Decimal256::from_ratio(i, 1u8);
+        let (c, c_neg) = sub_sign(exp, big_k - Decimal256::one());
+        term = term * c * x / Uint256::from(i);
+        if term.is_zero() {
+            break;
+        }
+
+        if x_neg {
+            negative = !negative;
+        }
+        if c_neg {
+            negative = !negative;
+        }
+
+        if negative {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+
+        i += 1;
+    }
+
+    sum
+}
+
+fn sub_sign(a: Decimal256, b: Decimal256) -> (Decimal256, bool) {
+    if a >= b {
+        (a - b, false)
+    } else {
+        (b - a, true)
+    }
+}
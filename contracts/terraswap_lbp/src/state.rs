@@ -0,0 +1,32 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Decimal256};
+use cw_storage_plus::Item;
+use terraswap::asset::AssetInfoRaw;
+
+use crate::math::compute_weights;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub asset_infos: [AssetInfoRaw; 2],
+    pub start_weights: [Decimal256; 2],
+    pub end_weights: [Decimal256; 2],
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl Config {
+    pub fn weights(&self, time: u64) -> [Decimal256; 2] {
+        compute_weights(
+            self.start_weights,
+            self.end_weights,
+            self.start_time,
+            self.end_time,
+            time,
+        )
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
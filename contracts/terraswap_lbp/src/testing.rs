@@ -0,0 +1,400 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::math::pow;
+use terraswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal, Decimal256, Env,
+    OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use std::str::FromStr;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::lbp::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, WeightsResponse,
+};
+use terraswap::pair::{ReverseSimulationResponse, SimulationResponse};
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.to_string(),
+    }
+}
+
+fn dec(value: &str) -> Decimal256 {
+    Decimal256::from_str(value).unwrap()
+}
+
+fn env_at(offset: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(offset);
+    env
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    let start_time = mock_env().block.time.seconds() + 100;
+    InstantiateMsg {
+        owner: "owner0000".to_string(),
+        asset_infos: [token("asset0000"), native("uusd")],
+        start_weights: [dec("0.9"), dec("0.1")],
+        end_weights: [dec("0.5"), dec("0.5")],
+        start_time,
+        end_time: start_time + 1000,
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    deps
+}
+
+fn with_pools(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    token: u128,
+    uusd: u128,
+) {
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(token))],
+    )]);
+    deps.querier
+        .with_balance(&[(&MOCK_CONTRACT_ADDR.to_string(), vec![coin(uusd, "uusd")])]);
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = setup();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    let msg = instantiate_msg();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            asset_infos: msg.asset_infos,
+            start_weights: msg.start_weights,
+            end_weights: msg.end_weights,
+            start_time: msg.start_time,
+            end_time: msg.end_time,
+        }
+    );
+
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("addr0000", &[]);
+
+    let mut msg = instantiate_msg();
+    msg.asset_infos = [native("uusd"), native("uusd")];
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::SameAssets {}));
+
+    let mut msg = instantiate_msg();
+    msg.end_weights = [dec("1"), Decimal256::zero()];
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::InvalidZeroWeight {}));
+
+    let mut msg = instantiate_msg();
+    msg.end_time = msg.start_time;
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidTimes {}));
+}
+
+#[test]
+fn query_weights() {
+    let deps = setup();
+
+    let weights_at = |offset: u64| -> [Decimal256; 2] {
+        let res: WeightsResponse =
+            from_binary(&query(deps.as_ref(), env_at(offset), QueryMsg::Weights {}).unwrap())
+                .unwrap();
+        res.weights
+    };
+
+    assert_eq!(weights_at(0), [dec("0.9"), dec("0.1")]);
+    assert_eq!(weights_at(100), [dec("0.9"), dec("0.1")]);
+    assert_eq!(weights_at(350), [dec("0.8"), dec("0.2")]);
+    assert_eq!(weights_at(600), [dec("0.7"), dec("0.3")]);
+    assert_eq!(weights_at(1100), [dec("0.5"), dec("0.5")]);
+    assert_eq!(weights_at(5000), [dec("0.5"), dec("0.5")]);
+}
+
+#[test]
+fn provide_and_withdraw_liquidity() {
+    let mut deps = setup();
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: token("asset0000"),
+                amount: Uint128::from(1_000_000u128),
+            },
+            Asset {
+                info: native("uusd"),
+                amount: Uint128::from(100_000u128),
+            },
+        ],
+    };
+
+    // only the owner can deposit
+    let info = mock_info("addr0000", &coins(100_000, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("owner0000", &coins(100_000, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "owner0000".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::from(1_000_000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    with_pools(&mut deps, 1_000_000, 100_000);
+
+    // the pool is locked during the sale
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(100),
+        info.clone(),
+        ExecuteMsg::WithdrawLiquidity {},
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::SaleInProgress {
+            end_time: instantiate_msg().end_time
+        })
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1100),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::WithdrawLiquidity {},
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1100),
+        info,
+        ExecuteMsg::WithdrawLiquidity {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "owner0000".to_string(),
+                    amount: Uint128::from(1_000_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner0000".to_string(),
+                amount: coins(100_000, "uusd"),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn swap() {
+    let mut deps = setup();
+    with_pools(&mut deps, 1_000_000, 101_000);
+
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(1_000u128),
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+    };
+
+    // swaps are disabled until the sale starts
+    let info = mock_info("addr0000", &coins(1_000, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::SwapNotStarted {
+            start_time: instantiate_msg().start_time
+        })
+    );
+
+    // weights are 0.7 / 0.3 halfway through the sale
+    // return = 1_000_000 * (1 - (100_000 / 101_000) ^ (0.3 / 0.7)) = 4_255
+    // minus the commission of 13
+    let res = execute(deps.as_mut(), env_at(600), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(4_242u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "swap"),
+            attr("sender", "addr0000"),
+            attr("receiver", "addr0000"),
+            attr("offer_asset", "uusd"),
+            attr("ask_asset", "asset0000"),
+            attr("offer_amount", "1000"),
+            attr("return_amount", "4242"),
+            attr("spread_amount", "30"),
+            attr("commission_amount", "13"),
+            attr("offer_weight", "0.3"),
+            attr("ask_weight", "0.7"),
+        ]
+    );
+
+    // the pool is not drained by a single swap
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(60_000u128),
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+    };
+    with_pools(&mut deps, 1_000_000, 160_000);
+    let info = mock_info("addr0000", &coins(60_000, "uusd"));
+    let res = execute(deps.as_mut(), env_at(600), info, msg);
+    assert_eq!(res, Err(ContractError::MaxRatioExceeded {}));
+
+    // cw20 offer with a max spread
+    with_pools(&mut deps, 1_010_000, 100_000);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(10_000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            belief_price: None,
+            max_spread: Some(Decimal::percent(2)),
+            to: Some("addr0001".to_string()),
+            deadline: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        env_at(600),
+        mock_info("asset0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("receiver", "addr0001")));
+
+    let res = execute(deps.as_mut(), env_at(600), mock_info("asset0001", &[]), msg);
+    assert_eq!(res, Err(ContractError::AssetMismatch {}));
+
+    // cw20 offers must come through the cw20 hook
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: token("asset0000"),
+            amount: Uint128::from(10_000u128),
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+    };
+    let res = execute(deps.as_mut(), env_at(600), mock_info("addr0000", &[]), msg);
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+}
+
+#[test]
+fn simulation() {
+    let mut deps = setup();
+    with_pools(&mut deps, 1_000_000, 100_000);
+
+    let res: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env_at(600),
+            QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: native("uusd"),
+                    amount: Uint128::from(1_000u128),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.return_amount, Uint128::from(4_242u128));
+
+    // the reverse simulation asks for at least the simulated offer
+    let res: ReverseSimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env_at(600),
+            QueryMsg::ReverseSimulation {
+                ask_asset: Asset {
+                    info: token("asset0000"),
+                    amount: Uint128::from(4_242u128),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.offer_amount, Uint128::from(1_000u128));
+    assert_eq!(res.commission_amount, Uint128::from(13u128));
+}
+
+#[test]
+fn weighted_pow() {
+    let assert_close = |value: Decimal256, expected: &str| {
+        let expected = dec(expected);
+        let diff = if value > expected {
+            value - expected
+        } else {
+            expected - value
+        };
+        assert!(diff < dec("0.000000001"), "{} != {}", value, expected);
+    };
+
+    assert_close(pow(dec("0.5"), dec("1.5")).unwrap(), "0.353553390593273762");
+    assert_close(pow(dec("1.5"), dec("0.5")).unwrap(), "1.224744871391589049");
+    assert_close(pow(dec("0.9"), dec("9")).unwrap(), "0.387420489");
+    assert_close(
+        pow(dec("0.99"), dec("0.111111")).unwrap(),
+        "0.998883920413600",
+    );
+    assert_eq!(
+        pow(dec("2"), dec("0.5")),
+        Err(ContractError::PowOutOfRange {})
+    );
+}
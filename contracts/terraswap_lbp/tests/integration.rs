@@ -0,0 +1,215 @@
+use cosmwasm_std::{to_binary, Addr, Decimal256, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use std::str::FromStr;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::lbp::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, WeightsResponse};
+use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+
+const OWNER: &str = "owner0000";
+const USER: &str = "addr0000";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_token::contract::execute,
+        terraswap_token::contract::instantiate,
+        terraswap_token::contract::query,
+    ))
+}
+
+fn lbp_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_lbp::contract::execute,
+        terraswap_lbp::contract::instantiate,
+        terraswap_lbp::contract::query,
+    ))
+}
+
+fn query_token_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    res.balance
+}
+
+fn instantiate_token(app: &mut App, code_id: u64, symbol: &str) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &TokenInstantiateMsg {
+            name: "terraswap token".to_string(),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: OWNER.to_string(),
+                amount: Uint128::from(1_000_000_000u128),
+            }],
+            mint: None,
+        },
+        &[],
+        "token",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn token_launch() {
+    let mut app = App::default();
+
+    let token_code_id = app.store_code(token_contract());
+    let lbp_code_id = app.store_code(lbp_contract());
+
+    let launch_token = instantiate_token(&mut app, token_code_id, "LCH");
+    let usd_token = instantiate_token(&mut app, token_code_id, "USD");
+
+    let start_time = app.block_info().time.seconds() + 100;
+    let lbp = app
+        .instantiate_contract(
+            lbp_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                owner: OWNER.to_string(),
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: launch_token.to_string(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: usd_token.to_string(),
+                    },
+                ],
+                start_weights: [
+                    Decimal256::from_str("0.9").unwrap(),
+                    Decimal256::from_str("0.1").unwrap(),
+                ],
+                end_weights: [
+                    Decimal256::from_str("0.5").unwrap(),
+                    Decimal256::from_str("0.5").unwrap(),
+                ],
+                start_time,
+                end_time: start_time + 1000,
+            },
+            &[],
+            "lbp",
+            None,
+        )
+        .unwrap();
+
+    for (token, amount) in [(&launch_token, 1_000_000u128), (&usd_token, 100_000u128)] {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: lbp.to_string(),
+                amount: Uint128::from(amount),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        lbp.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: launch_token.to_string(),
+                    },
+                    amount: Uint128::from(1_000_000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: usd_token.to_string(),
+                    },
+                    amount: Uint128::from(100_000u128),
+                },
+            ],
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        usd_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: USER.to_string(),
+            amount: Uint128::from(1_000u128),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // halfway through the sale
+    app.update_block(|block| block.time = block.time.plus_seconds(600));
+
+    let res: WeightsResponse = app
+        .wrap()
+        .query_wasm_smart(&lbp, &QueryMsg::Weights {})
+        .unwrap();
+    assert_eq!(
+        res.weights,
+        [
+            Decimal256::from_str("0.7").unwrap(),
+            Decimal256::from_str("0.3").unwrap(),
+        ]
+    );
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        usd_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: lbp.to_string(),
+            amount: Uint128::from(1_000u128),
+            msg: to_binary(&Cw20HookMsg::Swap {
+                belief_price: None,
+                max_spread: None,
+                to: None,
+                deadline: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_token_balance(&app, &launch_token, USER),
+        Uint128::from(4_242u128)
+    );
+
+    // the owner takes out the pool once the sale has ended
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        lbp.clone(),
+        &ExecuteMsg::WithdrawLiquidity {},
+        &[],
+    )
+    .unwrap_err();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(500));
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        lbp.clone(),
+        &ExecuteMsg::WithdrawLiquidity {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_token_balance(&app, &launch_token, lbp.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_token_balance(&app, &usd_token, OWNER),
+        Uint128::from(1_000_000_000u128)
+    );
+}
@@ -0,0 +1,573 @@
+use crate::error::ContractError;
+use crate::state::{orders, price_key, Config, Order, CONFIG, LAST_ORDER_ID};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order as StorageOrder, Response, StdResult, Uint128, WasmMsg,
+};
+
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::limit_order::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderResponse,
+    OrdersResponse, QueryMsg,
+};
+use terraswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
+use terraswap::querier::{query_pair_info, simulate};
+use terraswap::util::migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:terraswap-limit-order";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    assert_keeper_tip(msg.keeper_tip)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            terraswap_factory: deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(&msg.terraswap_factory)?.as_str())?,
+            keeper_tip: msg.keeper_tip,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::SubmitOrder {
+            offer_asset,
+            ask_asset_info,
+            price,
+            fee_tier,
+        } => {
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            offer_asset.assert_sent_native_token_balance(&info)?;
+            submit_order(
+                deps,
+                info.sender,
+                offer_asset,
+                ask_asset_info,
+                price,
+                fee_tier,
+            )
+        }
+        ExecuteMsg::CancelOrder { order_id } => cancel_order(deps, info, order_id),
+        ExecuteMsg::Execute { order_ids } => execute_orders(env, info, order_ids),
+        ExecuteMsg::UpdateConfig { owner, keeper_tip } => {
+            update_config(deps, info, owner, keeper_tip)
+        }
+        ExecuteMsg::FillOrder { order_id, keeper } => {
+            let keeper = deps.api.addr_validate(&keeper)?;
+            fill_order(deps, env, info, order_id, keeper)
+        }
+        ExecuteMsg::SettleOrder {
+            order_id,
+            keeper,
+            prev_ask_balance,
+        } => {
+            let keeper = deps.api.addr_validate(&keeper)?;
+            settle_order(deps, env, info, order_id, keeper, prev_ask_balance)
+        }
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::SubmitOrder {
+            ask_asset_info,
+            price,
+            fee_tier,
+        } => {
+            let maker = deps.api.addr_validate(&cw20_msg.sender)?;
+            submit_order(
+                deps,
+                maker,
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender.to_string(),
+                    },
+                    amount: cw20_msg.amount,
+                },
+                ask_asset_info,
+                price,
+                fee_tier,
+            )
+        }
+    }
+}
+
+pub fn submit_order(
+    deps: DepsMut,
+    maker: Addr,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    price: Decimal,
+    fee_tier: Option<u16>,
+) -> Result<Response, ContractError> {
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    if price.is_zero() {
+        return Err(ContractError::InvalidZeroPrice {});
+    }
+
+    if offer_asset.info.equal(&ask_asset_info) {
+        return Err(ContractError::SameAssets {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_info = query_pair_info(
+        &deps.querier,
+        deps.api.addr_humanize(&config.terraswap_factory)?,
+        &[offer_asset.info.clone(), ask_asset_info.clone()],
+        fee_tier,
+    )?;
+
+    let order_id = LAST_ORDER_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LAST_ORDER_ID.save(deps.storage, &order_id)?;
+
+    orders().save(
+        deps.storage,
+        order_id,
+        &Order {
+            order_id,
+            maker: deps.api.addr_canonicalize(maker.as_str())?,
+            pair_addr: deps.api.addr_canonicalize(&pair_info.contract_addr)?,
+            offer_asset: offer_asset.to_raw(deps.api)?,
+            ask_asset_info: ask_asset_info.to_raw(deps.api)?,
+            price,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "submit_order"),
+        ("order_id", &order_id.to_string()),
+        ("maker", maker.as_str()),
+        ("offer_asset", &offer_asset.to_string()),
+        ("ask_asset_info", &ask_asset_info.to_string()),
+        ("price", &price.to_string()),
+    ]))
+}
+
+// Only the maker can execute it
+pub fn cancel_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let order: Order = orders().load(deps.storage, order_id)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != order.maker {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    orders().remove(deps.storage, order_id)?;
+
+    let offer_asset = order.offer_asset.to_normal(deps.api)?;
+    Ok(Response::new()
+        .add_message(offer_asset.clone().into_msg(info.sender)?)
+        .add_attributes(vec![
+            ("action", "cancel_order"),
+            ("order_id", &order_id.to_string()),
+            ("refund_asset", &offer_asset.to_string()),
+        ]))
+}
+
+// Anyone can execute it, each order is filled by its own message
+// so the limit price is checked against the pool after the previous fills
+pub fn execute_orders(
+    env: Env,
+    info: MessageInfo,
+    order_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    if order_ids.is_empty() {
+        return Err(ContractError::NoOrderIds {});
+    }
+
+    let messages = order_ids
+        .iter()
+        .map(|order_id| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::FillOrder {
+                    order_id: *order_id,
+                    keeper: info.sender.to_string(),
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "execute_orders"))
+}
+
+// Only the contract itself can execute it
+pub fn fill_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+    keeper: Addr,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let skipped = Response::new().add_attributes(vec![
+        ("action", "fill_order"),
+        ("order_id", &order_id.to_string()),
+        ("filled", "false"),
+    ]);
+
+    // the order can be cancelled or filled earlier in the batch
+    let order = match orders().may_load(deps.storage, order_id)? {
+        Some(order) => order,
+        None => return Ok(skipped),
+    };
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_addr = deps.api.addr_humanize(&order.pair_addr)?;
+    let offer_asset = order.offer_asset.to_normal(deps.api)?;
+    let ask_asset_info = order.ask_asset_info.to_normal(deps.api)?;
+
+    let return_amount =
+        simulate(&deps.querier, pair_addr.clone(), &offer_asset, None)?.return_amount;
+    let tip_amount = return_amount * config.keeper_tip;
+    if return_amount.checked_sub(tip_amount)? < order.min_ask_amount() {
+        return Ok(skipped);
+    }
+
+    let prev_ask_balance =
+        ask_asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+
+    let swap_msg = match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair_addr.to_string(),
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+                deadline: None,
+                referral_address: None,
+                referral_commission: None,
+            })?,
+            funds: coins(offer_asset.amount.u128(), denom),
+        }),
+        AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair_addr.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: None,
+                    to: None,
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })?,
+            })?,
+            funds: vec![],
+        }),
+    };
+
+    Ok(Response::new()
+        .add_messages(vec![
+            swap_msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::SettleOrder {
+                    order_id,
+                    keeper: keeper.to_string(),
+                    prev_ask_balance,
+                })?,
+                funds: vec![],
+            }),
+        ])
+        .add_attributes(vec![
+            ("action", "fill_order"),
+            ("order_id", &order_id.to_string()),
+            ("filled", "true"),
+        ]))
+}
+
+// Only the contract itself can execute it
+pub fn settle_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+    keeper: Addr,
+    prev_ask_balance: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let order: Order = orders().load(deps.storage, order_id)?;
+    let ask_asset_info = order.ask_asset_info.to_normal(deps.api)?;
+
+    let ask_balance =
+        ask_asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+    let received_amount = ask_balance.checked_sub(prev_ask_balance)?;
+    let tip_amount = received_amount * config.keeper_tip;
+    let ask_amount = received_amount.checked_sub(tip_amount)?;
+
+    // the whole fill is reverted when the swap paid less than the simulation
+    let min_ask_amount = order.min_ask_amount();
+    if ask_amount < min_ask_amount {
+        return Err(ContractError::LimitPriceNotMet {
+            min_ask_amount: min_ask_amount.to_string(),
+            ask_amount: ask_amount.to_string(),
+        });
+    }
+
+    orders().remove(deps.storage, order_id)?;
+
+    let maker = deps.api.addr_humanize(&order.maker)?;
+    let mut messages: Vec<CosmosMsg> = vec![Asset {
+        info: ask_asset_info.clone(),
+        amount: ask_amount,
+    }
+    .into_msg(maker.clone())?];
+    if !tip_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: ask_asset_info,
+                amount: tip_amount,
+            }
+            .into_msg(keeper.clone())?,
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "settle_order"),
+        ("order_id", &order_id.to_string()),
+        ("maker", maker.as_str()),
+        ("ask_amount", &ask_amount.to_string()),
+        ("keeper", keeper.as_str()),
+        ("tip_amount", &tip_amount.to_string()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    keeper_tip: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(keeper_tip) = keeper_tip {
+        assert_keeper_tip(keeper_tip)?;
+        config.keeper_tip = keeper_tip;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn assert_keeper_tip(keeper_tip: Decimal) -> Result<(), ContractError> {
+    if keeper_tip >= Decimal::one() {
+        return Err(ContractError::InvalidKeeperTip {});
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Order { order_id } => to_binary(&query_order(deps, order_id)?),
+        QueryMsg::Orders {
+            maker,
+            start_after,
+            limit,
+        } => to_binary(&query_orders(deps, maker, start_after, limit)?),
+        QueryMsg::OrderBook {
+            offer_asset_info,
+            ask_asset_info,
+            fee_tier,
+            start_after,
+            limit,
+        } => to_binary(&query_order_book(
+            deps,
+            offer_asset_info,
+            ask_asset_info,
+            fee_tier,
+            start_after,
+            limit,
+        )?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let resp = ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        terraswap_factory: deps
+            .api
+            .addr_humanize(&config.terraswap_factory)?
+            .to_string(),
+        keeper_tip: config.keeper_tip,
+    };
+
+    Ok(resp)
+}
+
+pub fn query_order(deps: Deps, order_id: u64) -> StdResult<OrderResponse> {
+    let order: Order = orders().load(deps.storage, order_id)?;
+
+    order_to_response(deps.api, order)
+}
+
+pub fn query_orders(
+    deps: Deps,
+    maker: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OrdersResponse> {
+    let maker = deps.api.addr_canonicalize(&maker)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let orders = orders()
+        .idx
+        .maker
+        .prefix(maker.to_vec())
+        .range(deps.storage, start, None, StorageOrder::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, order) = item?;
+            order_to_response(deps.api, order)
+        })
+        .collect::<StdResult<Vec<OrderResponse>>>()?;
+
+    Ok(OrdersResponse { orders })
+}
+
+pub fn query_order_book(
+    deps: Deps,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+    fee_tier: Option<u16>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OrdersResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let pair_info = query_pair_info(
+        &deps.querier,
+        deps.api.addr_humanize(&config.terraswap_factory)?,
+        &[offer_asset_info.clone(), ask_asset_info],
+        fee_tier,
+    )?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after {
+        Some(order_id) => {
+            let order: Order = orders().load(deps.storage, order_id)?;
+            Some(Bound::exclusive((price_key(order.price), order_id)))
+        }
+        None => None,
+    };
+
+    let orders = orders()
+        .idx
+        .book
+        .sub_prefix((
+            deps.api
+                .addr_canonicalize(&pair_info.contract_addr)?
+                .to_vec(),
+            offer_asset_info.to_raw(deps.api)?.as_bytes().to_vec(),
+        ))
+        .range(deps.storage, start, None, StorageOrder::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, order) = item?;
+            order_to_response(deps.api, order)
+        })
+        .collect::<StdResult<Vec<OrderResponse>>>()?;
+
+    Ok(OrdersResponse { orders })
+}
+
+fn order_to_response(api: &dyn Api, order: Order) -> StdResult<OrderResponse> {
+    Ok(OrderResponse {
+        order_id: order.order_id,
+        maker: api.addr_humanize(&order.maker)?.to_string(),
+        pair_addr: api.addr_humanize(&order.pair_addr)?.to_string(),
+        offer_asset: order.offer_asset.to_normal(api)?,
+        ask_asset_info: order.ask_asset_info.to_normal(api)?,
+        price: order.price,
+    })
+}
+
+const TARGET_CONTRACT_VERSION: &str = "0.1.0";
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    migrate_version(
+        deps,
+        TARGET_CONTRACT_VERSION,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+
+    Ok(Response::default())
+}
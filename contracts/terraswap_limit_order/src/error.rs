@@ -0,0 +1,35 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Invalid zero price")]
+    InvalidZeroPrice {},
+
+    #[error("Keeper tip must be less than 1")]
+    InvalidKeeperTip {},
+
+    #[error("Offer and ask assets must be different")]
+    SameAssets {},
+
+    #[error("Must provide order ids")]
+    NoOrderIds {},
+
+    #[error("Limit price not met ({min_ask_amount} > {ask_amount})")]
+    LimitPriceNotMet {
+        min_ask_amount: String,
+        ask_amount: String,
+    },
+}
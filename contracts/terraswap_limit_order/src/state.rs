@@ -0,0 +1,71 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Decimal, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use terraswap::asset::{AssetInfoRaw, AssetRaw};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub terraswap_factory: CanonicalAddr,
+    pub keeper_tip: Decimal,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const LAST_ORDER_ID: Item<u64> = Item::new("last_order_id");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Order {
+    pub order_id: u64,
+    pub maker: CanonicalAddr,
+    pub pair_addr: CanonicalAddr,
+    pub offer_asset: AssetRaw,
+    pub ask_asset_info: AssetInfoRaw,
+    pub price: Decimal,
+}
+
+impl Order {
+    /// The maker receives at least this amount after the keeper tip
+    pub fn min_ask_amount(&self) -> Uint128 {
+        self.offer_asset.amount * self.price
+    }
+}
+
+pub struct OrderIndexes<'a> {
+    // key : (pair, offer asset info, price) / orders are sorted by the limit price
+    pub book: MultiIndex<'a, (Vec<u8>, Vec<u8>, u128), Order, u64>,
+    pub maker: MultiIndex<'a, Vec<u8>, Order, u64>,
+}
+
+impl<'a> IndexList<Order> for OrderIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Order>> + '_> {
+        let v: Vec<&dyn Index<Order>> = vec![&self.book, &self.maker];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn orders<'a>() -> IndexedMap<'a, u64, Order, OrderIndexes<'a>> {
+    let indexes = OrderIndexes {
+        book: MultiIndex::new(
+            |o: &Order| {
+                (
+                    o.pair_addr.to_vec(),
+                    o.offer_asset.info.as_bytes().to_vec(),
+                    price_key(o.price),
+                )
+            },
+            "orders",
+            "orders__book",
+        ),
+        maker: MultiIndex::new(|o: &Order| o.maker.to_vec(), "orders", "orders__maker"),
+    };
+
+    IndexedMap::new("orders", indexes)
+}
+
+/// The decimal atomics keep the price order in the index
+pub fn price_key(price: Decimal) -> u128 {
+    price.atomics().u128()
+}
@@ -0,0 +1,614 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::price_key;
+use terraswap::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal, OwnedDeps, StdError,
+    SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use terraswap::asset::{Asset, AssetInfo, PairInfo};
+use terraswap::limit_order::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, OrderResponse, OrdersResponse,
+    QueryMsg,
+};
+use terraswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.to_string(),
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_terraswap_factory(
+        &[(
+            &"uusdasset0000".to_string(),
+            &PairInfo {
+                asset_infos: [native("uusd"), token("asset0000")],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: "liquidity0000".to_string(),
+                asset_decimals: [6u8, 6u8],
+                fee_tier: 30,
+            },
+        )],
+        &[("uusd".to_string(), 6u8)],
+    );
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "factory0000".to_string(),
+        keeper_tip: Decimal::percent(1),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps
+}
+
+fn submit_native_order(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    maker: &str,
+    amount: u128,
+    price: Decimal,
+) {
+    let msg = ExecuteMsg::SubmitOrder {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(amount),
+        },
+        ask_asset_info: token("asset0000"),
+        price,
+        fee_tier: None,
+    };
+    let info = mock_info(maker, &coins(amount, "uusd"));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn query_order_book(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    start_after: Option<u64>,
+) -> Vec<u64> {
+    let res: OrdersResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OrderBook {
+                offer_asset_info: native("uusd"),
+                ask_asset_info: token("asset0000"),
+                start_after,
+                limit: None,
+                fee_tier: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    res.orders.iter().map(|o| o.order_id).collect()
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = setup();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            terraswap_factory: "factory0000".to_string(),
+            keeper_tip: Decimal::percent(1),
+        }
+    );
+
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        terraswap_factory: "factory0000".to_string(),
+        keeper_tip: Decimal::one(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidKeeperTip {}));
+}
+
+#[test]
+fn submit_order() {
+    let mut deps = setup();
+
+    // the escrowed amount must be sent
+    let msg = ExecuteMsg::SubmitOrder {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: token("asset0000"),
+        price: Decimal::one(),
+        fee_tier: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(
+        res,
+        Err(ContractError::Std(StdError::generic_err(
+            "Native token balance mismatch between the argument and the transferred"
+        )))
+    );
+
+    let msg = ExecuteMsg::SubmitOrder {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: token("asset0000"),
+        price: Decimal::zero(),
+        fee_tier: None,
+    };
+    let info = mock_info("addr0000", &coins(100, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidZeroPrice {}));
+
+    let msg = ExecuteMsg::SubmitOrder {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: native("uusd"),
+        price: Decimal::one(),
+        fee_tier: None,
+    };
+    let info = mock_info("addr0000", &coins(100, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::SameAssets {}));
+
+    // no pair exists
+    let msg = ExecuteMsg::SubmitOrder {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: native("uluna"),
+        price: Decimal::one(),
+        fee_tier: None,
+    };
+    let info = mock_info("addr0000", &coins(100, "uusd"));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+    let msg = ExecuteMsg::SubmitOrder {
+        offer_asset: Asset {
+            info: native("uusd"),
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: token("asset0000"),
+        price: Decimal::percent(150),
+        fee_tier: None,
+    };
+    let info = mock_info("addr0000", &coins(100, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "submit_order"),
+            attr("order_id", "1"),
+            attr("maker", "addr0000"),
+            attr("offer_asset", "100uusd"),
+            attr("ask_asset_info", "asset0000"),
+            attr("price", "1.5"),
+        ]
+    );
+
+    // the other side of the pair is escrowed through cw20
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(200u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            ask_asset_info: native("uusd"),
+            price: Decimal::percent(50),
+            fee_tier: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res: OrderResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Order { order_id: 2 }).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        OrderResponse {
+            order_id: 2,
+            maker: "addr0001".to_string(),
+            pair_addr: "pair0000".to_string(),
+            offer_asset: Asset {
+                info: token("asset0000"),
+                amount: Uint128::from(200u128),
+            },
+            ask_asset_info: native("uusd"),
+            price: Decimal::percent(50),
+        }
+    );
+}
+
+#[test]
+fn query_orders() {
+    let mut deps = setup();
+
+    submit_native_order(&mut deps, "addr0000", 100, Decimal::percent(150));
+    submit_native_order(&mut deps, "addr0001", 100, Decimal::percent(90));
+    submit_native_order(&mut deps, "addr0000", 100, Decimal::percent(120));
+    submit_native_order(&mut deps, "addr0001", 100, Decimal::percent(90));
+
+    // sorted by the limit price, and by id for the same price
+    assert_eq!(query_order_book(&deps, None), vec![2, 4, 3, 1]);
+    assert_eq!(query_order_book(&deps, Some(4)), vec![3, 1]);
+
+    // the other side is empty
+    let res: OrdersResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OrderBook {
+                offer_asset_info: token("asset0000"),
+                ask_asset_info: native("uusd"),
+                start_after: None,
+                limit: None,
+                fee_tier: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.orders, vec![]);
+
+    let res: OrdersResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Orders {
+                maker: "addr0000".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.orders.iter().map(|o| o.order_id).collect::<Vec<u64>>(),
+        vec![1, 3]
+    );
+
+    let res: OrdersResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Orders {
+                maker: "addr0001".to_string(),
+                start_after: Some(2),
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.orders.iter().map(|o| o.order_id).collect::<Vec<u64>>(),
+        vec![4]
+    );
+}
+
+#[test]
+fn cancel_order() {
+    let mut deps = setup();
+
+    submit_native_order(&mut deps, "addr0000", 100, Decimal::one());
+
+    let msg = ExecuteMsg::CancelOrder { order_id: 1 };
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![coin(100, "uusd")],
+        }))]
+    );
+
+    assert_eq!(query_order_book(&deps, None), Vec::<u64>::new());
+}
+
+#[test]
+fn execute_orders() {
+    let mut deps = setup();
+
+    // the mock pair returns the offer amount as it is
+    // 1000 - 10 (1% tip) >= 1000 * 0.9
+    submit_native_order(&mut deps, "addr0000", 1000, Decimal::percent(90));
+    // 1000 - 10 (1% tip) < 1000 * 1
+    submit_native_order(&mut deps, "addr0000", 1000, Decimal::one());
+
+    let msg = ExecuteMsg::Execute { order_ids: vec![] };
+    let info = mock_info("keeper0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::NoOrderIds {}));
+
+    let msg = ExecuteMsg::Execute {
+        order_ids: vec![1, 2],
+    };
+    let info = mock_info("keeper0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![1u64, 2u64]
+            .into_iter()
+            .map(|order_id| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::FillOrder {
+                    order_id,
+                    keeper: "keeper0000".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })))
+            .collect::<Vec<SubMsg>>()
+    );
+
+    // internal messages
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+    )]);
+    let msg = ExecuteMsg::FillOrder {
+        order_id: 1,
+        keeper: "keeper0000".to_string(),
+    };
+    let info = mock_info("keeper0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: native("uusd"),
+                        amount: Uint128::from(1000u128),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    to: None,
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })
+                .unwrap(),
+                funds: coins(1000, "uusd"),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::SettleOrder {
+                    order_id: 1,
+                    keeper: "keeper0000".to_string(),
+                    prev_ask_balance: Uint128::zero(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // the limit price is not met
+    let msg = ExecuteMsg::FillOrder {
+        order_id: 2,
+        keeper: "keeper0000".to_string(),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fill_order"),
+            attr("order_id", "2"),
+            attr("filled", "false"),
+        ]
+    );
+}
+
+#[test]
+fn fill_token_order() {
+    let mut deps = setup();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            ask_asset_info: native("uusd"),
+            price: Decimal::percent(90),
+            fee_tier: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier
+        .with_balance(&[(&MOCK_CONTRACT_ADDR.to_string(), vec![coin(500, "uusd")])]);
+
+    let msg = ExecuteMsg::FillOrder {
+        order_id: 1,
+        keeper: "keeper0000".to_string(),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair0000".to_string(),
+                    amount: Uint128::from(1000u128),
+                    msg: to_binary(&PairCw20HookMsg::Swap {
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                        deadline: None,
+                        referral_address: None,
+                        referral_commission: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::SettleOrder {
+                    order_id: 1,
+                    keeper: "keeper0000".to_string(),
+                    prev_ask_balance: Uint128::from(500u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn settle_order() {
+    let mut deps = setup();
+
+    submit_native_order(&mut deps, "addr0000", 1000, Decimal::percent(90));
+
+    let msg = ExecuteMsg::SettleOrder {
+        order_id: 1,
+        keeper: "keeper0000".to_string(),
+        prev_ask_balance: Uint128::from(100u128),
+    };
+    let info = mock_info("keeper0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // the swap paid less than the limit price
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::LimitPriceNotMet {
+            min_ask_amount: "900".to_string(),
+            ask_amount: "891".to_string(),
+        })
+    );
+
+    // 1000 is received, 1% is the keeper tip
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1100u128))],
+    )]);
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(990u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "keeper0000".to_string(),
+                    amount: Uint128::from(10u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // the settled order is removed
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Order { order_id: 1 });
+    assert!(res.is_err());
+}
+
+#[test]
+fn update_config() {
+    let mut deps = setup();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("owner0001".to_string()),
+        keeper_tip: Some(Decimal::percent(2)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::UpdateConfig {
+            owner: None,
+            keeper_tip: Some(Decimal::one()),
+        },
+    );
+    assert_eq!(res, Err(ContractError::InvalidKeeperTip {}));
+
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner0001".to_string(),
+            terraswap_factory: "factory0000".to_string(),
+            keeper_tip: Decimal::percent(2),
+        }
+    );
+}
+
+#[test]
+fn price_key_keeps_price_order() {
+    assert_eq!(
+        price_key(Decimal::percent(150)),
+        1_500_000_000_000_000_000u128
+    );
+
+    // prices too large to be multiplied by the decimal fractional
+    let max = Decimal::MAX;
+    let lower = max - Decimal::one();
+    assert_eq!(price_key(max), max.atomics().u128());
+    assert!(price_key(lower) < price_key(max));
+}
@@ -0,0 +1,253 @@
+use cosmwasm_std::{to_binary, Addr, Decimal, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::factory::{
+    ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg,
+};
+use terraswap::limit_order::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, OrdersResponse, QueryMsg};
+use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+
+const OWNER: &str = "owner0000";
+const MAKER: &str = "addr0000";
+const KEEPER: &str = "keeper0000";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_token::contract::execute,
+        terraswap_token::contract::instantiate,
+        terraswap_token::contract::query,
+    ))
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_pair::contract::execute,
+            terraswap_pair::contract::instantiate,
+            terraswap_pair::contract::query,
+        )
+        .with_reply(terraswap_pair::contract::reply),
+    )
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_factory::contract::execute,
+            terraswap_factory::contract::instantiate,
+            terraswap_factory::contract::query,
+        )
+        .with_reply(terraswap_factory::contract::reply),
+    )
+}
+
+fn limit_order_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_limit_order::contract::execute,
+        terraswap_limit_order::contract::instantiate,
+        terraswap_limit_order::contract::query,
+    ))
+}
+
+fn query_token_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    res.balance
+}
+
+fn instantiate_token(app: &mut App, code_id: u64, symbol: &str) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &TokenInstantiateMsg {
+            name: "terraswap token".to_string(),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: OWNER.to_string(),
+                amount: Uint128::from(1_000_000_000u128),
+            }],
+            mint: None,
+        },
+        &[],
+        "token",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn fill_orders_against_pair() {
+    let mut app = App::default();
+
+    let token_code_id = app.store_code(token_contract());
+    let pair_code_id = app.store_code(pair_contract());
+    let factory_code_id = app.store_code(factory_contract());
+    let limit_order_code_id = app.store_code(limit_order_contract());
+
+    let offer_token = instantiate_token(&mut app, token_code_id, "OFR");
+    let ask_token = instantiate_token(&mut app, token_code_id, "ASK");
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(OWNER),
+            &FactoryInstantiateMsg {
+                pair_code_id,
+                token_code_id,
+                timelock_delay: 0,
+            },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    let offer_asset_info = AssetInfo::Token {
+        contract_addr: offer_token.to_string(),
+    };
+    let ask_asset_info = AssetInfo::Token {
+        contract_addr: ask_token.to_string(),
+    };
+
+    // create the pair with 1:1 initial liquidity
+    for token in [&offer_token, &ask_token] {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: factory.to_string(),
+                amount: Uint128::from(100_000_000u128),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &FactoryExecuteMsg::CreatePair {
+            assets: [
+                Asset {
+                    info: offer_asset_info.clone(),
+                    amount: Uint128::from(100_000_000u128),
+                },
+                Asset {
+                    info: ask_asset_info.clone(),
+                    amount: Uint128::from(100_000_000u128),
+                },
+            ],
+            label: None,
+            fee_tier: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let limit_order = app
+        .instantiate_contract(
+            limit_order_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                terraswap_factory: factory.to_string(),
+                keeper_tip: Decimal::percent(1),
+            },
+            &[],
+            "limit order",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        offer_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: MAKER.to_string(),
+            amount: Uint128::from(2_000_000u128),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the pool pays about 0.98 per offer unit
+    for price in [Decimal::percent(90), Decimal::percent(99)] {
+        app.execute_contract(
+            Addr::unchecked(MAKER),
+            offer_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: limit_order.to_string(),
+                amount: Uint128::from(1_000_000u128),
+                msg: to_binary(&Cw20HookMsg::SubmitOrder {
+                    ask_asset_info: ask_asset_info.clone(),
+                    price,
+                    fee_tier: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    assert_eq!(
+        query_token_balance(&app, &offer_token, limit_order.as_str()),
+        Uint128::from(2_000_000u128)
+    );
+
+    // anyone can execute the orders, the second one is skipped
+    app.execute_contract(
+        Addr::unchecked(KEEPER),
+        limit_order.clone(),
+        &ExecuteMsg::Execute {
+            order_ids: vec![1, 2],
+        },
+        &[],
+    )
+    .unwrap();
+
+    // 987_128 is swapped, 9_871 is the keeper tip
+    assert_eq!(
+        query_token_balance(&app, &ask_token, MAKER),
+        Uint128::from(977_257u128)
+    );
+    assert_eq!(
+        query_token_balance(&app, &ask_token, KEEPER),
+        Uint128::from(9_871u128)
+    );
+    assert_eq!(
+        query_token_balance(&app, &ask_token, limit_order.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_token_balance(&app, &offer_token, limit_order.as_str()),
+        Uint128::from(1_000_000u128)
+    );
+
+    let res: OrdersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &limit_order,
+            &QueryMsg::OrderBook {
+                offer_asset_info,
+                ask_asset_info,
+                start_after: None,
+                limit: None,
+                fee_tier: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.orders.iter().map(|o| o.order_id).collect::<Vec<u64>>(),
+        vec![2]
+    );
+}
@@ -5,8 +5,8 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use terraswap::asset::PairInfo;
 use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse,
+    Cw20HookMsg, DynamicFeeResponse, ExecuteMsg, InstantiateMsg, LpTokenValueResponse, MigrateMsg,
+    PoolResponse, PriceResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
 };
 
 fn main() {
@@ -21,7 +21,10 @@ fn main() {
     export_schema(&schema_for!(Cw20HookMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(PairInfo), &out_dir);
+    export_schema(&schema_for!(DynamicFeeResponse), &out_dir);
+    export_schema(&schema_for!(LpTokenValueResponse), &out_dir);
     export_schema(&schema_for!(PoolResponse), &out_dir);
+    export_schema(&schema_for!(PriceResponse), &out_dir);
     export_schema(&schema_for!(ReverseSimulationResponse), &out_dir);
     export_schema(&schema_for!(SimulationResponse), &out_dir);
 }
@@ -1,14 +1,14 @@
 use crate::error::ContractError;
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::PAIR_INFO;
+use crate::state::{Volatility, DYNAMIC_FEE, FACTORY, PAIR_INFO, VOLATILITY};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    coins, from_binary, to_binary, Addr, BankMsg, Binary, CanonicalAddr, CosmosMsg, Decimal,
-    Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult,
-    SubMsg, Uint128, Uint256, WasmMsg,
+    coins, from_binary, to_binary, Addr, BankMsg, Binary, CanonicalAddr, ContractInfoResponse,
+    CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, QueryRequest,
+    Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, Uint256, WasmMsg, WasmQuery,
 };
 
 use cw2::set_contract_version;
@@ -16,16 +16,19 @@ use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use protobuf::Message;
 use std::cmp::Ordering;
 use std::convert::TryInto;
-use std::ops::Mul;
 use std::str::FromStr;
 use terraswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
 use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
+    Cw20HookMsg, DynamicFeeConfig, DynamicFeeResponse, ExecuteMsg, InstantiateMsg,
+    LpTokenValueResponse, MigrateMsg, PoolResponse, PriceResponse, QueryMsg,
     ReverseSimulationResponse, SimulationResponse,
 };
-use terraswap::querier::query_token_info;
+use terraswap::querier::{query_factory_config, query_token_info};
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
-use terraswap::util::migrate_version;
+use terraswap::util::{
+    compute_initial_share, fee_tier_rate, migrate_version, pair_label, DEFAULT_FEE_TIER, FEE_TIERS,
+    MINIMUM_LIQUIDITY_AMOUNT,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:terraswap-pair";
@@ -33,20 +36,28 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_REPLY_ID: u64 = 1;
 
-/// Commission rate == 0.3%
-const COMMISSION_RATE: u64 = 3;
-
-const MINIMUM_LIQUIDITY_AMOUNT: u128 = 1_000;
-
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let fee_tier = msg.fee_tier.unwrap_or(DEFAULT_FEE_TIER);
+    if !FEE_TIERS.contains(&fee_tier) {
+        return Err(StdError::generic_err(format!(
+            "fee tier {} is not supported",
+            fee_tier
+        )));
+    }
+
+    FACTORY.save(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+    )?;
+
     let pair_info: &PairInfoRaw = &PairInfoRaw {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         liquidity_token: CanonicalAddr::from(vec![]),
@@ -55,6 +66,7 @@ pub fn instantiate(
             msg.asset_infos[1].to_raw(deps.api)?,
         ],
         asset_decimals: msg.asset_decimals,
+        fee_tier,
     };
 
     PAIR_INFO.save(deps.storage, pair_info)?;
@@ -75,7 +87,13 @@ pub fn instantiate(
                 }),
             })?,
             funds: vec![],
-            label: "lp".to_string(),
+            label: pair_label(
+                "terraswap lp",
+                &[
+                    query_label_symbol(&deps.querier, &msg.asset_infos[0]),
+                    query_label_symbol(&deps.querier, &msg.asset_infos[1]),
+                ],
+            ),
         }
         .into(),
         gas_limit: None,
@@ -84,6 +102,13 @@ pub fn instantiate(
     }))
 }
 
+// the denom or the cw20 symbol, tokens without token info fall back to their address
+fn query_label_symbol(querier: &QuerierWrapper, asset_info: &AssetInfo) -> String {
+    asset_info
+        .query_symbol(querier)
+        .unwrap_or_else(|_| asset_info.to_string())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -113,6 +138,8 @@ pub fn execute(
             max_spread,
             to,
             deadline,
+            referral_address,
+            referral_commission,
         } => {
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Unauthorized {});
@@ -134,8 +161,11 @@ pub fn execute(
                 max_spread,
                 to_addr,
                 deadline,
+                referral_address,
+                referral_commission,
             )
         }
+        ExecuteMsg::UpdateDynamicFee { dynamic_fee } => update_dynamic_fee(deps, info, dynamic_fee),
     }
 }
 
@@ -153,6 +183,8 @@ pub fn receive_cw20(
             max_spread,
             to,
             deadline,
+            referral_address,
+            referral_commission,
         }) => {
             // only asset contract can execute this message
             let mut authorized: bool = false;
@@ -192,6 +224,8 @@ pub fn receive_cw20(
                 max_spread,
                 to_addr,
                 deadline,
+                referral_address,
+                referral_commission,
             )
         }
         Ok(Cw20HookMsg::WithdrawLiquidity {
@@ -252,6 +286,7 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     assert_deadline(env.block.time.seconds(), deadline)?;
+    assert_not_paused(deps.as_ref())?;
 
     for asset in assets.iter() {
         asset.assert_sent_native_token_balance(&info)?;
@@ -286,15 +321,7 @@ pub fn provide_liquidity(
     let total_share = query_token_info(&deps.querier, liquidity_token)?.total_supply;
     let share: Uint128 = if total_share.is_zero() {
         // Initial share = collateral amount
-        let deposit0: Uint256 = deposits[0].into();
-        let deposit1: Uint256 = deposits[1].into();
-        let share: Uint128 = match (Decimal256::from_ratio(deposit0.mul(deposit1), 1u8).sqrt()
-            * Uint256::from(1u8))
-        .try_into()
-        {
-            Ok(share) => share,
-            Err(e) => return Err(ContractError::ConversionOverflowError(e)),
-        };
+        let share: Uint128 = compute_initial_share(deposits)?;
 
         // the initial liquidity is deducted by MINIMUM_LIQUIDITY_AMOUNT
         // to protect a pair from malicious provision blocking
@@ -461,6 +488,31 @@ pub fn withdraw_liquidity(
         ]))
 }
 
+pub fn update_dynamic_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    dynamic_fee: Option<DynamicFeeConfig>,
+) -> Result<Response, ContractError> {
+    // permission check
+    let factory = deps.api.addr_humanize(&FACTORY.load(deps.storage)?)?;
+    if info.sender.as_str() != query_factory_config(&deps.querier, factory)?.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match dynamic_fee {
+        Some(dynamic_fee) => {
+            assert_dynamic_fee(&dynamic_fee)?;
+            DYNAMIC_FEE.save(deps.storage, &dynamic_fee)?;
+        }
+        None => {
+            DYNAMIC_FEE.remove(deps.storage);
+            VOLATILITY.remove(deps.storage);
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_dynamic_fee"))
+}
+
 // CONTRACT - a user must do token approval
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
@@ -473,11 +525,26 @@ pub fn swap(
     max_spread: Option<Decimal>,
     to: Option<Addr>,
     deadline: Option<u64>,
+    referral_address: Option<String>,
+    referral_commission: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     assert_deadline(env.block.time.seconds(), deadline)?;
+    assert_not_paused(deps.as_ref())?;
 
     offer_asset.assert_sent_native_token_balance(&info)?;
 
+    let referral = match (referral_address, referral_commission) {
+        (Some(referral_address), Some(referral_commission)) => {
+            assert_referral_commission(deps.as_ref(), referral_commission)?;
+            Some((
+                deps.api.addr_validate(&referral_address)?,
+                referral_commission,
+            ))
+        }
+        (None, None) => None,
+        _ => return Err(ContractError::InvalidReferral {}),
+    };
+
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
 
     let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
@@ -511,9 +578,31 @@ pub fn swap(
         return Err(ContractError::AssetMismatch {});
     }
 
-    let offer_amount = offer_asset.amount;
-    let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_amount)?;
+    // the referral commission is deducted from the offer amount before the swap
+    let referral_amount = match &referral {
+        Some((_, referral_commission)) => offer_asset.amount * *referral_commission,
+        None => Uint128::zero(),
+    };
+    let offer_amount = offer_asset.amount.checked_sub(referral_amount)?;
+
+    // the volatility is sampled on the reserves before the swap
+    let pool_amounts = if offer_pool.info.equal(&pools[0].info) {
+        [offer_pool.amount, ask_pool.amount]
+    } else {
+        [ask_pool.amount, offer_pool.amount]
+    };
+    let (commission_rate, volatility) =
+        compute_commission_rate(deps.as_ref(), env.block.height, pool_amounts)?;
+    if let Some(volatility) = &volatility {
+        VOLATILITY.save(deps.storage, volatility)?;
+    }
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_amount,
+        commission_rate,
+    )?;
 
     let return_asset = Asset {
         info: ask_pool.info.clone(),
@@ -524,7 +613,10 @@ pub fn swap(
     assert_max_spread(
         belief_price,
         max_spread,
-        offer_asset.clone(),
+        Asset {
+            info: offer_asset.info.clone(),
+            amount: offer_amount,
+        },
         return_asset.clone(),
         spread_amount,
         offer_decimal,
@@ -538,32 +630,64 @@ pub fn swap(
         messages.push(return_asset.into_msg(receiver.clone())?);
     }
 
+    let mut attributes = vec![
+        ("action", "swap".to_string()),
+        ("sender", sender.to_string()),
+        ("receiver", receiver.to_string()),
+        ("offer_asset", offer_asset.info.to_string()),
+        ("ask_asset", ask_pool.info.to_string()),
+        ("offer_amount", offer_asset.amount.to_string()),
+        ("return_amount", return_amount.to_string()),
+        ("spread_amount", spread_amount.to_string()),
+        ("commission_amount", commission_amount.to_string()),
+    ];
+
+    if volatility.is_some() {
+        attributes.push(("commission_rate", commission_rate.to_string()));
+    }
+
+    if let Some((referral_address, _)) = referral {
+        if !referral_amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: offer_asset.info.clone(),
+                    amount: referral_amount,
+                }
+                .into_msg(referral_address.clone())?,
+            );
+        }
+
+        attributes.push(("referral_address", referral_address.to_string()));
+        attributes.push(("referral_amount", referral_amount.to_string()));
+    }
+
     // 1. send collateral token from the contract to a user
-    // 2. send inactive commission to collector
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "swap"),
-        ("sender", sender.as_str()),
-        ("receiver", receiver.as_str()),
-        ("offer_asset", &offer_asset.info.to_string()),
-        ("ask_asset", &ask_pool.info.to_string()),
-        ("offer_amount", &offer_amount.to_string()),
-        ("return_amount", &return_amount.to_string()),
-        ("spread_amount", &spread_amount.to_string()),
-        ("commission_amount", &commission_amount.to_string()),
-    ]))
+    // 2. send referral commission to the referrer
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Pair {} => Ok(to_binary(&query_pair_info(deps)?)?),
         QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps)?)?),
-        QueryMsg::Simulation { offer_asset } => {
-            Ok(to_binary(&query_simulation(deps, offer_asset)?)?)
-        }
+        QueryMsg::Simulation {
+            offer_asset,
+            referral_commission,
+        } => Ok(to_binary(&query_simulation(
+            deps,
+            env,
+            offer_asset,
+            referral_commission,
+        )?)?),
         QueryMsg::ReverseSimulation { ask_asset } => {
-            Ok(to_binary(&query_reverse_simulation(deps, ask_asset)?)?)
+            Ok(to_binary(&query_reverse_simulation(deps, env, ask_asset)?)?)
         }
+        QueryMsg::Price { base_asset } => Ok(to_binary(&query_price(deps, base_asset)?)?),
+        QueryMsg::LpTokenValue { prices } => Ok(to_binary(&query_lp_token_value(deps, prices)?)?),
+        QueryMsg::DynamicFee {} => Ok(to_binary(&query_dynamic_fee(deps, env)?)?),
     }
 }
 
@@ -594,7 +718,9 @@ pub fn query_pool(deps: Deps) -> Result<PoolResponse, ContractError> {
 
 pub fn query_simulation(
     deps: Deps,
+    env: Env,
     offer_asset: Asset,
+    referral_commission: Option<Decimal>,
 ) -> Result<SimulationResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
 
@@ -613,18 +739,34 @@ pub fn query_simulation(
         return Err(ContractError::AssetMismatch {});
     }
 
-    let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount)?;
+    let referral_amount = match referral_commission {
+        Some(referral_commission) => {
+            assert_referral_commission(deps, referral_commission)?;
+            offer_asset.amount * referral_commission
+        }
+        None => Uint128::zero(),
+    };
+
+    let (commission_rate, _) =
+        compute_commission_rate(deps, env.block.height, [pools[0].amount, pools[1].amount])?;
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_asset.amount.checked_sub(referral_amount)?,
+        commission_rate,
+    )?;
 
     Ok(SimulationResponse {
         return_amount,
         spread_amount,
         commission_amount,
+        referral_amount,
     })
 }
 
 pub fn query_reverse_simulation(
     deps: Deps,
+    env: Env,
     ask_asset: Asset,
 ) -> Result<ReverseSimulationResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
@@ -644,8 +786,14 @@ pub fn query_reverse_simulation(
         return Err(ContractError::AssetMismatch {});
     }
 
-    let (offer_amount, spread_amount, commission_amount) =
-        compute_offer_amount(offer_pool.amount, ask_pool.amount, ask_asset.amount)?;
+    let (commission_rate, _) =
+        compute_commission_rate(deps, env.block.height, [pools[0].amount, pools[1].amount])?;
+    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_asset.amount,
+        commission_rate,
+    )?;
 
     Ok(ReverseSimulationResponse {
         offer_amount,
@@ -654,17 +802,204 @@ pub fn query_reverse_simulation(
     })
 }
 
+pub fn query_dynamic_fee(deps: Deps, env: Env) -> Result<DynamicFeeResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+
+    let (commission_rate, volatility) =
+        compute_commission_rate(deps, env.block.height, [pools[0].amount, pools[1].amount])?;
+
+    Ok(DynamicFeeResponse {
+        dynamic_fee: DYNAMIC_FEE.may_load(deps.storage)?,
+        volatility: volatility.map_or_else(Decimal256::zero, |v| v.volatility),
+        commission_rate,
+    })
+}
+
+pub fn query_price(deps: Deps, base_asset: AssetInfo) -> Result<PriceResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+
+    let (base_pool, quote_pool, base_decimal, quote_decimal) = if base_asset.equal(&pools[0].info) {
+        (
+            pools[0].amount,
+            pools[1].amount,
+            pair_info.asset_decimals[0],
+            pair_info.asset_decimals[1],
+        )
+    } else if base_asset.equal(&pools[1].info) {
+        (
+            pools[1].amount,
+            pools[0].amount,
+            pair_info.asset_decimals[1],
+            pair_info.asset_decimals[0],
+        )
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    if base_pool.is_zero() || quote_pool.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    Ok(PriceResponse {
+        price: compute_price(base_pool, quote_pool, base_decimal, quote_decimal)?,
+    })
+}
+
+pub fn query_lp_token_value(
+    deps: Deps,
+    prices: [Decimal256; 2],
+) -> Result<LpTokenValueResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+    let total_share: Uint128 = query_token_info(
+        &deps.querier,
+        deps.api.addr_humanize(&pair_info.liquidity_token)?,
+    )?
+    .total_supply;
+
+    if total_share.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    Ok(LpTokenValueResponse {
+        value: compute_lp_token_value([pools[0].amount, pools[1].amount], prices, total_share)?,
+    })
+}
+
+/// The fair reserves are the reserves the pool would hold if its marginal price
+/// matched `prices`, which keeps k constant:
+/// value = 2 * sqrt(k * p0 * p1) / total_share
+///       = 2 * sqrt(r0 * p0) * sqrt(r1 * p1) / total_share
+fn compute_lp_token_value(
+    pools: [Uint128; 2],
+    prices: [Decimal256; 2],
+    total_share: Uint128,
+) -> StdResult<Decimal256> {
+    let value0 = Decimal256::from_ratio(pools[0], 1u8).checked_mul(prices[0])?;
+    let value1 = Decimal256::from_ratio(pools[1], 1u8).checked_mul(prices[1])?;
+
+    let pool_value = value0
+        .sqrt()
+        .checked_mul(value1.sqrt())?
+        .checked_mul(Decimal256::from_ratio(2u8, 1u8))?;
+
+    Ok(pool_value / Uint256::from(total_share))
+}
+
+/// price = (quote_pool / 10^quote_decimal) / (base_pool / 10^base_decimal)
+fn compute_price(
+    base_pool: Uint128,
+    quote_pool: Uint128,
+    base_decimal: u8,
+    quote_decimal: u8,
+) -> StdResult<Decimal256> {
+    let base_pool: Uint256 = base_pool.into();
+    let quote_pool: Uint256 = quote_pool.into();
+
+    let (numerator, denominator) = match base_decimal.cmp(&quote_decimal) {
+        Ordering::Greater => {
+            let diff_decimal = Uint256::from(10u8).pow((base_decimal - quote_decimal).into());
+            (quote_pool.checked_mul(diff_decimal)?, base_pool)
+        }
+        Ordering::Less => {
+            let diff_decimal = Uint256::from(10u8).pow((quote_decimal - base_decimal).into());
+            (quote_pool, base_pool.checked_mul(diff_decimal)?)
+        }
+        Ordering::Equal => (quote_pool, base_pool),
+    };
+
+    Decimal256::checked_from_ratio(numerator, denominator)
+        .map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+/// Returns the commission rate of a swap at `height` and, in dynamic fee mode,
+/// the volatility estimate updated with the current `pools`
+fn compute_commission_rate(
+    deps: Deps,
+    height: u64,
+    pools: [Uint128; 2],
+) -> StdResult<(Decimal256, Option<Volatility>)> {
+    let dynamic_fee = match DYNAMIC_FEE.may_load(deps.storage)? {
+        Some(dynamic_fee) => dynamic_fee,
+        None => {
+            let fee_tier = PAIR_INFO.load(deps.storage)?.fee_tier;
+            return Ok((fee_tier_rate(fee_tier), None));
+        }
+    };
+
+    let volatility = update_volatility(
+        VOLATILITY.may_load(deps.storage)?,
+        &dynamic_fee,
+        height,
+        pools,
+    );
+
+    let commission_rate =
+        dynamic_fee.min_commission_rate + dynamic_fee.volatility_multiplier * volatility.volatility;
+
+    Ok((
+        std::cmp::min(commission_rate, dynamic_fee.max_commission_rate),
+        Some(volatility),
+    ))
+}
+
+/// The price is sampled once per block, so swaps inside a block
+/// cannot move the estimate used by the following swaps of the block
+fn update_volatility(
+    volatility: Option<Volatility>,
+    dynamic_fee: &DynamicFeeConfig,
+    height: u64,
+    pools: [Uint128; 2],
+) -> Volatility {
+    let volatility = volatility.unwrap_or(Volatility {
+        last_price: Decimal256::zero(),
+        last_height: 0,
+        volatility: Decimal256::zero(),
+    });
+
+    if volatility.last_height >= height || pools[0].is_zero() || pools[1].is_zero() {
+        return volatility;
+    }
+
+    let price = Decimal256::from_ratio(pools[1], pools[0]);
+    if volatility.last_price.is_zero() {
+        return Volatility {
+            last_price: price,
+            last_height: height,
+            volatility: Decimal256::zero(),
+        };
+    }
+
+    let price_move = if price > volatility.last_price {
+        (price - volatility.last_price) / volatility.last_price
+    } else {
+        (volatility.last_price - price) / volatility.last_price
+    };
+
+    Volatility {
+        last_price: price,
+        last_height: height,
+        volatility: dynamic_fee.smoothing_factor * price_move
+            + (Decimal256::one() - dynamic_fee.smoothing_factor) * volatility.volatility,
+    }
+}
+
 fn compute_swap(
     offer_pool: Uint128,
     ask_pool: Uint128,
     offer_amount: Uint128,
+    commission_rate: Decimal256,
 ) -> StdResult<(Uint128, Uint128, Uint128)> {
     let offer_pool: Uint256 = offer_pool.into();
     let ask_pool: Uint256 = ask_pool.into();
     let offer_amount: Uint256 = offer_amount.into();
 
-    let commission_rate = Decimal256::permille(COMMISSION_RATE);
-
     // offer => ask
     // ask_amount = (ask_pool - cp / (offer_pool + offer_amount)) * (1 - commission_rate)
     let return_amount: Uint256 = (ask_pool * offer_amount) / (offer_pool + offer_amount);
@@ -691,9 +1026,14 @@ fn test_compute_swap_with_huge_pool_variance() {
     let ask_pool = Uint128::from(317u128);
 
     assert_eq!(
-        compute_swap(offer_pool, ask_pool, Uint128::from(1u128))
-            .unwrap()
-            .0,
+        compute_swap(
+            offer_pool,
+            ask_pool,
+            Uint128::from(1u128),
+            fee_tier_rate(DEFAULT_FEE_TIER)
+        )
+        .unwrap()
+        .0,
         Uint128::zero()
     );
 }
@@ -702,13 +1042,12 @@ fn compute_offer_amount(
     offer_pool: Uint128,
     ask_pool: Uint128,
     ask_amount: Uint128,
+    commission_rate: Decimal256,
 ) -> StdResult<(Uint128, Uint128, Uint128)> {
     let offer_pool: Uint256 = offer_pool.into();
     let ask_pool: Uint256 = ask_pool.into();
     let ask_amount: Uint256 = ask_amount.into();
 
-    let commission_rate = Decimal256::permille(COMMISSION_RATE);
-
     // ask => offer
     // offer_amount = cp / (ask_pool - ask_amount / (1 - commission_rate)) - offer_pool
     let cp: Uint256 = offer_pool * ask_pool;
@@ -853,6 +1192,50 @@ pub fn assert_minimum_assets(
     Ok(())
 }
 
+/// The referral commission is capped by `max_referral_commission` of the factory
+pub fn assert_referral_commission(
+    deps: Deps,
+    referral_commission: Decimal,
+) -> Result<(), ContractError> {
+    let factory = deps.api.addr_humanize(&FACTORY.load(deps.storage)?)?;
+    let max_referral_commission =
+        query_factory_config(&deps.querier, factory)?.max_referral_commission;
+
+    if referral_commission > max_referral_commission {
+        return Err(ContractError::MaxReferralCommissionExceeded {
+            max_referral_commission: max_referral_commission.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Swaps and deposits are rejected while the factory is paused, withdrawals are not.
+/// They are rejected as well when the factory config cannot be queried
+pub fn assert_not_paused(deps: Deps) -> Result<(), ContractError> {
+    let factory = deps.api.addr_humanize(&FACTORY.load(deps.storage)?)?;
+    if query_factory_config(&deps.querier, factory)?.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    Ok(())
+}
+
+/// The commission must stay in (0, 1) for the swap math,
+/// and the EMA needs a smoothing factor in (0, 1]
+pub fn assert_dynamic_fee(dynamic_fee: &DynamicFeeConfig) -> Result<(), ContractError> {
+    if dynamic_fee.min_commission_rate.is_zero()
+        || dynamic_fee.min_commission_rate > dynamic_fee.max_commission_rate
+        || dynamic_fee.max_commission_rate >= Decimal256::one()
+        || dynamic_fee.smoothing_factor.is_zero()
+        || dynamic_fee.smoothing_factor > Decimal256::one()
+    {
+        return Err(ContractError::InvalidDynamicFee {});
+    }
+
+    Ok(())
+}
+
 pub fn assert_deadline(blocktime: u64, deadline: Option<u64>) -> Result<(), ContractError> {
     if let Some(deadline) = deadline {
         if blocktime >= deadline {
@@ -863,9 +1246,22 @@ pub fn assert_deadline(blocktime: u64, deadline: Option<u64>) -> Result<(), Cont
     Ok(())
 }
 
-const TARGET_CONTRACT_VERSION: &str = "0.1.1";
+const TARGET_CONTRACT_VERSION: &str = "0.2.0";
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // the pairs instantiated before the factory was stored are administrated by the factory
+    if FACTORY.may_load(deps.storage)?.is_none() {
+        let contract_info: ContractInfoResponse =
+            deps.querier
+                .query(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+                    contract_addr: env.contract.address.to_string(),
+                }))?;
+        let admin = contract_info
+            .admin
+            .ok_or_else(|| StdError::generic_err("the factory of the pair is unknown"))?;
+        FACTORY.save(deps.storage, &deps.api.addr_canonicalize(&admin)?)?;
+    }
+
     migrate_version(
         deps,
         TARGET_CONTRACT_VERSION,
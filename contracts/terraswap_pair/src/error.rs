@@ -33,6 +33,21 @@ pub enum ContractError {
     #[error("Max slippage assertion")]
     MaxSlippageAssertion {},
 
+    #[error("Pool is empty")]
+    EmptyPool {},
+
+    #[error("Referral address and commission must be given together")]
+    InvalidReferral {},
+
+    #[error("Referral commission exceeds the maximum ({max_referral_commission})")]
+    MaxReferralCommissionExceeded { max_referral_commission: String },
+
+    #[error("Factory is paused")]
+    Paused {},
+
+    #[error("Invalid dynamic fee config")]
+    InvalidDynamicFee {},
+
     #[error("More initial liquidity needed ({min_lp_token} > {given_lp})")]
     MinimumLiquidityAmountError {
         min_lp_token: String,
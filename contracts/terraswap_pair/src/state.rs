@@ -1,4 +1,23 @@
+use cosmwasm_std::{CanonicalAddr, Decimal256};
 use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use terraswap::asset::PairInfoRaw;
+use terraswap::pair::DynamicFeeConfig;
 
 pub const PAIR_INFO: Item<PairInfoRaw> = Item::new("pair_info");
+
+// the factory which instantiated this pair, used to read the referral config
+// and the pause
+pub const FACTORY: Item<CanonicalAddr> = Item::new("factory");
+
+pub const DYNAMIC_FEE: Item<DynamicFeeConfig> = Item::new("dynamic_fee");
+pub const VOLATILITY: Item<Volatility> = Item::new("volatility");
+
+/// EMA of the relative price moves, sampled at the first swap of each block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Volatility {
+    pub last_price: Decimal256,
+    pub last_height: u64,
+    pub volatility: Decimal256,
+}
@@ -1,21 +1,24 @@
 use crate::contract::{
-    assert_deadline, assert_max_spread, assert_minimum_assets, execute, instantiate,
-    query_pair_info, query_pool, query_reverse_simulation, query_simulation, reply,
+    assert_deadline, assert_max_spread, assert_minimum_assets, execute, instantiate, migrate,
+    query_dynamic_fee, query_lp_token_value, query_pair_info, query_pool, query_price,
+    query_reverse_simulation, query_simulation, reply,
 };
 use crate::error::ContractError;
+use crate::state::FACTORY;
 use std::str::FromStr;
 use terraswap::mock_querier::mock_dependencies;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Reply, ReplyOn, Response, StdError, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, to_binary, Api, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, Reply, ReplyOn, Response,
+    StdError, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
 use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    Cw20HookMsg, DynamicFeeConfig, DynamicFeeResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    PoolResponse, ReverseSimulationResponse, SimulationResponse,
 };
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
 
@@ -34,6 +37,7 @@ fn proper_initialization() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        fee_tier: None,
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -57,7 +61,7 @@ fn proper_initialization() {
                 })
                 .unwrap(),
                 funds: vec![],
-                label: "lp".to_string(),
+                label: "terraswap lp uusd-asset0000".to_string(),
                 admin: None,
             }
             .into(),
@@ -125,6 +129,7 @@ fn provide_liquidity() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        fee_tier: None,
     };
 
     let env = mock_env();
@@ -550,6 +555,7 @@ fn withdraw_liquidity() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        fee_tier: None,
     };
 
     let env = mock_env();
@@ -742,6 +748,7 @@ fn try_native_to_token() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        fee_tier: None,
     };
 
     let env = mock_env();
@@ -777,6 +784,8 @@ fn try_native_to_token() {
         max_spread: None,
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
     let env = mock_env();
     let info = mock_info(
@@ -811,12 +820,14 @@ fn try_native_to_token() {
 
     let simulation_res: SimulationResponse = query_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
             amount: offer_amount,
         },
+        None,
     )
     .unwrap();
     assert_eq!(expected_return_amount, simulation_res.return_amount);
@@ -826,6 +837,7 @@ fn try_native_to_token() {
     // check reverse simulation res
     let reverse_simulation_res: ReverseSimulationResponse = query_reverse_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
@@ -918,6 +930,7 @@ fn try_token_to_native() {
         ],
         token_code_id: 10u64,
         asset_decimals: [8u8, 8u8],
+        fee_tier: None,
     };
 
     let env = mock_env();
@@ -953,6 +966,8 @@ fn try_token_to_native() {
         max_spread: None,
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -971,6 +986,8 @@ fn try_token_to_native() {
             max_spread: None,
             to: None,
             deadline: None,
+            referral_address: None,
+            referral_commission: None,
         })
         .unwrap(),
     });
@@ -1006,12 +1023,14 @@ fn try_token_to_native() {
 
     let simulation_res: SimulationResponse = query_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             amount: offer_amount,
             info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
         },
+        None,
     )
     .unwrap();
     assert_eq!(expected_return_amount, simulation_res.return_amount);
@@ -1021,6 +1040,7 @@ fn try_token_to_native() {
     // check reverse simulation res
     let reverse_simulation_res: ReverseSimulationResponse = query_reverse_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             amount: expected_return_amount,
             info: AssetInfo::NativeToken {
@@ -1081,6 +1101,8 @@ fn try_token_to_native() {
             max_spread: None,
             to: None,
             deadline: None,
+            referral_address: None,
+            referral_commission: None,
         })
         .unwrap(),
     });
@@ -1094,198 +1116,29 @@ fn try_token_to_native() {
 }
 
 #[test]
-fn test_max_spread() {
-    let offer_asset_info = AssetInfo::NativeToken {
-        denom: "offer_asset".to_string(),
-    };
-    let ask_asset_info = AssetInfo::NativeToken {
-        denom: "ask_asset_info".to_string(),
-    };
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info.clone(),
-            amount: Uint128::from(1200000000u128),
-        },
-        Asset {
-            info: ask_asset_info.clone(),
-            amount: Uint128::from(989999u128),
-        },
-        Uint128::zero(),
-        6u8,
-        6u8,
-    )
-    .unwrap_err();
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info.clone(),
-            amount: Uint128::from(1200000000u128),
-        },
-        Asset {
-            info: ask_asset_info.clone(),
-            amount: Uint128::from(990000u128),
-        },
-        Uint128::zero(),
-        6u8,
-        6u8,
-    )
-    .unwrap();
-
-    assert_max_spread(
-        None,
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info.clone(),
-            amount: Uint128::zero(),
-        },
-        Asset {
-            info: ask_asset_info.clone(),
-            amount: Uint128::from(989999u128),
-        },
-        Uint128::from(10001u128),
-        6u8,
-        6u8,
-    )
-    .unwrap_err();
-
-    assert_max_spread(
-        None,
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info,
-            amount: Uint128::zero(),
-        },
-        Asset {
-            info: ask_asset_info,
-            amount: Uint128::from(990000u128),
-        },
-        Uint128::from(10000u128),
-        6u8,
-        6u8,
-    )
-    .unwrap();
-}
-
-#[test]
-fn test_max_spread_with_diff_decimal() {
-    let token_addr = "ask_asset_info".to_string();
-
-    let mut deps = mock_dependencies(&[]);
-    deps.querier.with_token_balances(&[(
-        &token_addr,
-        &[(
-            &MOCK_CONTRACT_ADDR.to_string(),
-            &Uint128::from(10000000000u64),
-        )],
-    )]);
-    let offer_asset_info = AssetInfo::NativeToken {
-        denom: "offer_asset".to_string(),
-    };
-    let ask_asset_info = AssetInfo::Token {
-        contract_addr: token_addr.to_string(),
-    };
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info.clone(),
-            amount: Uint128::from(1200000000u128),
-        },
-        Asset {
-            info: ask_asset_info.clone(),
-            amount: Uint128::from(100000000u128),
-        },
-        Uint128::zero(),
-        6u8,
-        8u8,
-    )
-    .unwrap();
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info,
-            amount: Uint128::from(1200000000u128),
-        },
-        Asset {
-            info: ask_asset_info,
-            amount: Uint128::from(98999999u128),
-        },
-        Uint128::zero(),
-        6u8,
-        8u8,
-    )
-    .unwrap_err();
-
-    let offer_asset_info = AssetInfo::Token {
-        contract_addr: token_addr,
-    };
-    let ask_asset_info = AssetInfo::NativeToken {
-        denom: "offer_asset".to_string(),
-    };
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info.clone(),
-            amount: Uint128::from(120000000000u128),
-        },
-        Asset {
-            info: ask_asset_info.clone(),
-            amount: Uint128::from(1000000u128),
-        },
-        Uint128::zero(),
-        8u8,
-        6u8,
-    )
-    .unwrap();
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Asset {
-            info: offer_asset_info,
-            amount: Uint128::from(120000000000u128),
-        },
-        Asset {
-            info: ask_asset_info,
-            amount: Uint128::from(989999u128),
-        },
-        Uint128::zero(),
-        8u8,
-        6u8,
-    )
-    .unwrap_err();
-}
+fn try_native_to_token_with_referral() {
+    let total_share = Uint128::from(30000000000u128);
+    let asset_pool_amount = Uint128::from(20000000000u128);
+    let collateral_pool_amount = Uint128::from(30000000000u128);
+    let offer_amount = Uint128::from(1500000000u128);
 
-#[test]
-fn test_query_pool() {
-    let total_share_amount = Uint128::from(111u128);
-    let asset_0_amount = Uint128::from(222u128);
-    let asset_1_amount = Uint128::from(333u128);
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
-        amount: asset_0_amount,
+        amount: collateral_pool_amount + offer_amount, /* user deposit must be pre-applied */
     }]);
 
     deps.querier.with_token_balances(&[
         (
-            &"asset0000".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &asset_1_amount)],
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &total_share)],
         ),
         (
-            &"liquidity0000".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &total_share_amount)],
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &asset_pool_amount)],
         ),
     ]);
+    deps.querier
+        .with_max_referral_commission(Decimal::percent(1));
 
     let msg = InstantiateMsg {
         asset_infos: [
@@ -1298,11 +1151,11 @@ fn test_query_pool() {
         ],
         token_code_id: 10u64,
         asset_decimals: [6u8, 8u8],
+        fee_tier: None,
     };
 
     let env = mock_env();
-    let info = mock_info("addr0000", &[]);
-    // we can just call .unwrap() to assert this was a success
+    let info = mock_info("factory0000", &[]);
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     // store liquidity token
@@ -1321,26 +1174,994 @@ fn test_query_pool() {
 
     let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
-    let res: PoolResponse = query_pool(deps.as_ref()).unwrap();
-
-    assert_eq!(
-        res.assets,
-        [
-            Asset {
+    let swap_msg =
+        |referral_address: Option<&str>, referral_commission: Option<Decimal>| ExecuteMsg::Swap {
+            offer_asset: Asset {
                 info: AssetInfo::NativeToken {
                     denom: "uusd".to_string(),
                 },
-                amount: asset_0_amount
+                amount: offer_amount,
             },
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                amount: asset_1_amount
-            }
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            deadline: None,
+            referral_address: referral_address.map(|v| v.to_string()),
+            referral_commission,
+        };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+
+    // referral address and commission must be given together
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        swap_msg(None, Some(Decimal::percent(1))),
+    );
+    assert_eq!(res, Err(ContractError::InvalidReferral {}));
+
+    // exceeds the factory max_referral_commission
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        swap_msg(Some("referral0000"), Some(Decimal::percent(2))),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::MaxReferralCommissionExceeded {
+            max_referral_commission: "0.01".to_string()
+        })
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        swap_msg(Some("referral0000"), Some(Decimal::percent(1))),
+    )
+    .unwrap();
+
+    // 15 = 1500 * 0.01 is sent to the referrer and 1485 is swapped
+    // 943.306336 = 20000 * 1485 / (30000 + 1485)
+    // 2.829920 = 943.306336 * 0.003, round up
+    let expected_referral_amount = Uint128::from(15_000_000u128);
+    let expected_return_amount = Uint128::from(940_476_416u128);
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: expected_return_amount,
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "referral0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: expected_referral_amount,
+                }],
+            })),
         ]
     );
-    assert_eq!(res.total_share, total_share_amount);
+    // the offer amount is reported before the referral cut
+    assert_eq!(
+        res.attributes[5],
+        attr("offer_amount", "1500000000".to_string())
+    );
+    assert_eq!(
+        res.attributes[9..],
+        vec![
+            attr("referral_address", "referral0000"),
+            attr("referral_amount", expected_referral_amount.to_string()),
+        ]
+    );
+
+    // check simulation res
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: collateral_pool_amount,
+        }],
+    )]);
+
+    let simulation_res: SimulationResponse = query_simulation(
+        deps.as_ref(),
+        mock_env(),
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        Some(Decimal::percent(1)),
+    )
+    .unwrap();
+    assert_eq!(expected_return_amount, simulation_res.return_amount);
+    assert_eq!(expected_referral_amount, simulation_res.referral_amount);
+}
+
+#[test]
+fn try_swap_with_dynamic_fee() {
+    let pool_amount = Uint128::from(1_000_000_000u128);
+    let offer_amount = Uint128::from(1_000_000u128);
+
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: pool_amount + offer_amount, /* user deposit must be pre-applied */
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &pool_amount)],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 6u8],
+        fee_tier: None,
+    };
+
+    let info = mock_info("factory0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let dynamic_fee = DynamicFeeConfig {
+        min_commission_rate: Decimal256::permille(1),
+        max_commission_rate: Decimal256::percent(1),
+        volatility_multiplier: Decimal256::percent(10),
+        smoothing_factor: Decimal256::percent(50),
+    };
+
+    // only the factory owner can update the dynamic fee
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateDynamicFee {
+            dynamic_fee: Some(dynamic_fee.clone()),
+        },
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::UpdateDynamicFee {
+            dynamic_fee: Some(DynamicFeeConfig {
+                min_commission_rate: Decimal256::percent(2),
+                ..dynamic_fee.clone()
+            }),
+        },
+    );
+    assert_eq!(res, Err(ContractError::InvalidDynamicFee {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::UpdateDynamicFee {
+            dynamic_fee: Some(dynamic_fee.clone()),
+        },
+    )
+    .unwrap();
+
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+        referral_address: None,
+        referral_commission: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+
+    // the first swap only samples the price, so the minimum rate is used
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), swap_msg.clone()).unwrap();
+    assert!(res.attributes.contains(&attr("commission_rate", "0.001")));
+
+    // the price moved by 10% in the next block
+    let mut env = mock_env();
+    env.block.height += 1;
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: pool_amount,
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &pool_amount)],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_100_000_000u128),
+            )],
+        ),
+    ]);
+
+    // volatility = 0.5 * 0.1 + 0.5 * 0
+    // commission_rate = 0.001 + 0.1 * 0.05
+    let res = query_dynamic_fee(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(
+        res,
+        DynamicFeeResponse {
+            dynamic_fee: Some(dynamic_fee),
+            volatility: Decimal256::percent(5),
+            commission_rate: Decimal256::from_str("0.006").unwrap(),
+        }
+    );
+
+    // 1.098901 = 1100 * 1 / (1000 + 1), the simulation pays the same rate
+    // 0.006594 = 1.098901 * 0.006, round up
+    let simulation_res = query_simulation(
+        deps.as_ref(),
+        env.clone(),
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        None,
+    )
+    .unwrap();
+    assert_eq!(simulation_res.commission_amount, Uint128::from(6_594u128));
+
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: pool_amount + offer_amount,
+        }],
+    )]);
+    let res = execute(deps.as_mut(), env.clone(), info, swap_msg).unwrap();
+    assert!(res.attributes.contains(&attr("commission_rate", "0.006")));
+    assert!(res.attributes.contains(&attr("commission_amount", "6594")));
+
+    // disabling restores the flat commission
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        ExecuteMsg::UpdateDynamicFee { dynamic_fee: None },
+    )
+    .unwrap();
+    let res = query_dynamic_fee(deps.as_ref(), env).unwrap();
+    assert_eq!(
+        res,
+        DynamicFeeResponse {
+            dynamic_fee: None,
+            volatility: Decimal256::zero(),
+            commission_rate: Decimal256::permille(3),
+        }
+    );
+}
+
+#[test]
+fn swap_and_provide_while_factory_paused() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(200u128),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&"addr0000".to_string(), &Uint128::from(100u128))],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+        ),
+    ]);
+    deps.querier.with_factory_paused(true);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        fee_tier: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("factory0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let uusd = |amount: u128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    let msg = ExecuteMsg::Swap {
+        offer_asset: uusd(100),
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+        referral_address: None,
+        referral_commission: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::Paused {}));
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            uusd(100),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+        ],
+        receiver: None,
+        deadline: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::Paused {}));
+
+    // withdrawals are still honoured
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            min_assets: None,
+            deadline: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+    let info = mock_info("liquidity0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 3);
+}
+
+#[test]
+fn migrate_pair_without_factory() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(200u128),
+    }]);
+    deps.querier.with_factory_paused(true);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        fee_tier: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("factory0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // the pairs deployed before the factory was stored
+    FACTORY.remove(deps.as_mut().storage);
+    set_contract_version(deps.as_mut().storage, "crates.io:terraswap-pair", "0.2.0").unwrap();
+
+    deps.querier
+        .with_code_ids(&[(&MOCK_CONTRACT_ADDR.to_string(), 1u64)]);
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => {
+            assert_eq!(msg, "the factory of the pair is unknown")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    deps.querier
+        .with_admins(&[(&MOCK_CONTRACT_ADDR.to_string(), &"factory0000".to_string())]);
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    assert_eq!(
+        FACTORY.load(deps.as_ref().storage).unwrap(),
+        deps.api.addr_canonicalize("factory0000").unwrap()
+    );
+    assert_eq!(
+        get_contract_version(deps.as_ref().storage).unwrap().version,
+        env!("CARGO_PKG_VERSION")
+    );
+
+    // the pause of the factory is honoured again
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: None,
+        referral_address: None,
+        referral_commission: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_eq!(res, Err(ContractError::Paused {}));
+}
+
+#[test]
+fn test_max_spread() {
+    let offer_asset_info = AssetInfo::NativeToken {
+        denom: "offer_asset".to_string(),
+    };
+    let ask_asset_info = AssetInfo::NativeToken {
+        denom: "ask_asset_info".to_string(),
+    };
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info.clone(),
+            amount: Uint128::from(1200000000u128),
+        },
+        Asset {
+            info: ask_asset_info.clone(),
+            amount: Uint128::from(989999u128),
+        },
+        Uint128::zero(),
+        6u8,
+        6u8,
+    )
+    .unwrap_err();
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info.clone(),
+            amount: Uint128::from(1200000000u128),
+        },
+        Asset {
+            info: ask_asset_info.clone(),
+            amount: Uint128::from(990000u128),
+        },
+        Uint128::zero(),
+        6u8,
+        6u8,
+    )
+    .unwrap();
+
+    assert_max_spread(
+        None,
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info.clone(),
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: ask_asset_info.clone(),
+            amount: Uint128::from(989999u128),
+        },
+        Uint128::from(10001u128),
+        6u8,
+        6u8,
+    )
+    .unwrap_err();
+
+    assert_max_spread(
+        None,
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info,
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: ask_asset_info,
+            amount: Uint128::from(990000u128),
+        },
+        Uint128::from(10000u128),
+        6u8,
+        6u8,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_max_spread_with_diff_decimal() {
+    let token_addr = "ask_asset_info".to_string();
+
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &token_addr,
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(10000000000u64),
+        )],
+    )]);
+    let offer_asset_info = AssetInfo::NativeToken {
+        denom: "offer_asset".to_string(),
+    };
+    let ask_asset_info = AssetInfo::Token {
+        contract_addr: token_addr.to_string(),
+    };
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info.clone(),
+            amount: Uint128::from(1200000000u128),
+        },
+        Asset {
+            info: ask_asset_info.clone(),
+            amount: Uint128::from(100000000u128),
+        },
+        Uint128::zero(),
+        6u8,
+        8u8,
+    )
+    .unwrap();
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info,
+            amount: Uint128::from(1200000000u128),
+        },
+        Asset {
+            info: ask_asset_info,
+            amount: Uint128::from(98999999u128),
+        },
+        Uint128::zero(),
+        6u8,
+        8u8,
+    )
+    .unwrap_err();
+
+    let offer_asset_info = AssetInfo::Token {
+        contract_addr: token_addr,
+    };
+    let ask_asset_info = AssetInfo::NativeToken {
+        denom: "offer_asset".to_string(),
+    };
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info.clone(),
+            amount: Uint128::from(120000000000u128),
+        },
+        Asset {
+            info: ask_asset_info.clone(),
+            amount: Uint128::from(1000000u128),
+        },
+        Uint128::zero(),
+        8u8,
+        6u8,
+    )
+    .unwrap();
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Asset {
+            info: offer_asset_info,
+            amount: Uint128::from(120000000000u128),
+        },
+        Asset {
+            info: ask_asset_info,
+            amount: Uint128::from(989999u128),
+        },
+        Uint128::zero(),
+        8u8,
+        6u8,
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_query_pool() {
+    let total_share_amount = Uint128::from(111u128);
+    let asset_0_amount = Uint128::from(222u128);
+    let asset_1_amount = Uint128::from(333u128);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: asset_0_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &asset_1_amount)],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &total_share_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        fee_tier: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let res: PoolResponse = query_pool(deps.as_ref()).unwrap();
+
+    assert_eq!(
+        res.assets,
+        [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: asset_0_amount
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: asset_1_amount
+            }
+        ]
+    );
+    assert_eq!(res.total_share, total_share_amount);
+}
+
+#[test]
+fn fee_tier_commission() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1_000u128),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1_000u128))],
+    )]);
+
+    let mut msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        fee_tier: Some(7),
+    };
+
+    // unsupported tier
+    let info = mock_info("addr0000", &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "fee tier 7 is not supported")
+        }
+        _ => panic!("Must return generic error"),
+    }
+
+    msg.fee_tier = Some(5);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let pair_info: PairInfo = query_pair_info(deps.as_ref()).unwrap();
+    assert_eq!(pair_info.fee_tier, 5);
+
+    let res = query_dynamic_fee(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(res.commission_rate, Decimal256::from_ratio(5u16, 10_000u16));
+}
+
+#[test]
+fn test_query_price() {
+    // 30000 uusd (6 decimals) and 20000 asset0000 (8 decimals)
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(30_000_000_000u128),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(2_000_000_000_000u128),
+            )],
+        ),
+        (
+            &"asset0001".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 8u8],
+        fee_tier: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query_price(
+        deps.as_ref(),
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.price, Decimal256::from_str("1.5").unwrap());
+
+    let res = query_price(
+        deps.as_ref(),
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.price,
+        Decimal256::from_str("0.666666666666666666").unwrap()
+    );
+
+    let res = query_price(
+        deps.as_ref(),
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+    );
+    assert_eq!(res, Err(ContractError::AssetMismatch {}));
+
+    // empty pool has no price
+    deps.querier
+        .with_balance(&[(&MOCK_CONTRACT_ADDR.to_string(), vec![])]);
+    let res = query_price(
+        deps.as_ref(),
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+    );
+    assert_eq!(res, Err(ContractError::EmptyPool {}));
+}
+
+#[test]
+fn test_query_lp_token_value() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1_000_000u128),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(4_000_000u128),
+            )],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(2_000_000u128),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        asset_decimals: [6u8, 6u8],
+        fee_tier: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let prices = [Decimal256::one(), Decimal256::percent(25)];
+    let res = query_lp_token_value(deps.as_ref(), prices).unwrap();
+    assert_eq!(res.value, Decimal256::one());
+
+    // skew the reserves with the same k, as a large swap would do
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(4_000_000u128),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_000_000u128),
+            )],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(2_000_000u128),
+            )],
+        ),
+    ]);
+
+    // the spot value would be 2.125, the fair value is unchanged
+    let res = query_lp_token_value(deps.as_ref(), prices).unwrap();
+    assert_eq!(res.value, Decimal256::one());
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+    ]);
+    let res = query_lp_token_value(deps.as_ref(), prices);
+    assert_eq!(res, Err(ContractError::EmptyPool {}));
 }
 
 #[test]
@@ -2,8 +2,8 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 
@@ -13,8 +13,8 @@ use crate::state::{Config, CONFIG};
 use cw20::Cw20ReceiveMsg;
 use std::collections::HashMap;
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::SimulationResponse;
-use terraswap::querier::{query_pair_info, reverse_simulate, simulate};
+use terraswap::pair::{PriceResponse, SimulationResponse};
+use terraswap::querier::{query_pair_info, query_price, reverse_simulate, simulate};
 use terraswap::router::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
     SimulateSwapOperationsResponse, SwapOperation,
@@ -53,6 +53,8 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             minimum_receive,
             to,
             deadline,
+            referral_address,
+            referral_commission,
         } => {
             let api = deps.api;
             execute_swap_operations(
@@ -63,12 +65,16 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 minimum_receive,
                 optional_addr_validate(api, to)?,
                 deadline,
+                optional_addr_validate(api, referral_address)?,
+                referral_commission,
             )
         }
         ExecuteMsg::ExecuteSwapOperation {
             operation,
             to,
             deadline,
+            referral_address,
+            referral_commission,
         } => {
             let api = deps.api;
             execute_swap_operation(
@@ -78,6 +84,8 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 operation,
                 optional_addr_validate(api, to)?.map(|v| v.to_string()),
                 deadline,
+                referral_address,
+                referral_commission,
             )
         }
         ExecuteMsg::AssertMinimumReceive {
@@ -118,6 +126,8 @@ pub fn receive_cw20(
             minimum_receive,
             to,
             deadline,
+            referral_address,
+            referral_commission,
         } => {
             let api = deps.api;
             execute_swap_operations(
@@ -128,11 +138,14 @@ pub fn receive_cw20(
                 minimum_receive,
                 optional_addr_validate(api, to)?,
                 deadline,
+                optional_addr_validate(api, referral_address)?,
+                referral_commission,
             )
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operations(
     deps: DepsMut,
     env: Env,
@@ -141,6 +154,8 @@ pub fn execute_swap_operations(
     minimum_receive: Option<Uint128>,
     to: Option<Addr>,
     deadline: Option<u64>,
+    referral_address: Option<Addr>,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<Response> {
     let operations_len = operations.len();
     if operations_len == 0 {
@@ -158,6 +173,17 @@ pub fn execute_swap_operations(
         .into_iter()
         .map(|op| {
             operation_index += 1;
+
+            // the referral commission is taken from the first offer asset only
+            let (referral_address, referral_commission) = if operation_index == 1 {
+                (
+                    referral_address.as_ref().map(|v| v.to_string()),
+                    referral_commission,
+                )
+            } else {
+                (None, None)
+            };
+
             Ok(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 funds: vec![],
@@ -169,6 +195,8 @@ pub fn execute_swap_operations(
                         None
                     },
                     deadline,
+                    referral_address,
+                    referral_commission,
                 })?,
             }))
         })
@@ -220,13 +248,20 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::SimulateSwapOperations {
             offer_amount,
             operations,
-        } => to_binary(&simulate_swap_operations(deps, offer_amount, operations)?),
+            referral_commission,
+        } => to_binary(&simulate_swap_operations(
+            deps,
+            offer_amount,
+            operations,
+            referral_commission,
+        )?),
         QueryMsg::ReverseSimulateSwapOperations {
             ask_amount,
             operations,
         } => to_binary(&reverse_simulate_swap_operations(
             deps, ask_amount, operations,
         )?),
+        QueryMsg::Price { operations } => to_binary(&query_route_price(deps, operations)?),
     }
 }
 
@@ -246,6 +281,7 @@ fn simulate_swap_operations(
     deps: Deps,
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<SimulateSwapOperationsResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
     let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
@@ -256,16 +292,18 @@ fn simulate_swap_operations(
     }
 
     let mut offer_amount = offer_amount;
-    for operation in operations.into_iter() {
+    for (operation_index, operation) in operations.into_iter().enumerate() {
         match operation {
             SwapOperation::TerraSwap {
                 offer_asset_info,
                 ask_asset_info,
+                fee_tier,
             } => {
                 let pair_info: PairInfo = query_pair_info(
                     &deps.querier,
                     terraswap_factory.clone(),
                     &[offer_asset_info.clone(), ask_asset_info.clone()],
+                    fee_tier,
                 )?;
 
                 let res: SimulationResponse = simulate(
@@ -275,6 +313,12 @@ fn simulate_swap_operations(
                         info: offer_asset_info,
                         amount: offer_amount,
                     },
+                    // the referral commission is taken from the first offer asset only
+                    if operation_index == 0 {
+                        referral_commission
+                    } else {
+                        None
+                    },
                 )?;
 
                 offer_amount = res.return_amount;
@@ -305,6 +349,7 @@ fn reverse_simulate_swap_operations(
             SwapOperation::TerraSwap {
                 offer_asset_info,
                 ask_asset_info,
+                fee_tier,
             } => {
                 let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
 
@@ -314,6 +359,7 @@ fn reverse_simulate_swap_operations(
                     ask_amount,
                     offer_asset_info,
                     ask_asset_info,
+                    fee_tier,
                 )
                 .unwrap()
             }
@@ -323,17 +369,58 @@ fn reverse_simulate_swap_operations(
     Ok(SimulateSwapOperationsResponse { amount: ask_amount })
 }
 
+fn query_route_price(deps: Deps, operations: Vec<SwapOperation>) -> StdResult<PriceResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+
+    if operations.is_empty() {
+        return Err(StdError::generic_err("must provide operations"));
+    }
+
+    assert_operations(&operations)?;
+
+    let mut price = Decimal256::one();
+    for operation in operations.into_iter() {
+        match operation {
+            SwapOperation::TerraSwap {
+                offer_asset_info,
+                ask_asset_info,
+                fee_tier,
+            } => {
+                let pair_info: PairInfo = query_pair_info(
+                    &deps.querier,
+                    terraswap_factory.clone(),
+                    &[offer_asset_info.clone(), ask_asset_info],
+                    fee_tier,
+                )?;
+
+                let res: PriceResponse = query_price(
+                    &deps.querier,
+                    Addr::unchecked(pair_info.contract_addr),
+                    &offer_asset_info,
+                )?;
+
+                price = price.checked_mul(res.price)?;
+            }
+        }
+    }
+
+    Ok(PriceResponse { price })
+}
+
 fn reverse_simulate_return_amount(
     deps: Deps,
     factory: Addr,
     ask_amount: Uint128,
     offer_asset_info: AssetInfo,
     ask_asset_info: AssetInfo,
+    fee_tier: Option<u16>,
 ) -> StdResult<Uint128> {
     let pair_info: PairInfo = query_pair_info(
         &deps.querier,
         factory,
         &[offer_asset_info, ask_asset_info.clone()],
+        fee_tier,
     )?;
 
     let res = reverse_simulate(
@@ -355,6 +442,7 @@ fn assert_operations(operations: &[SwapOperation]) -> StdResult<()> {
             SwapOperation::TerraSwap {
                 offer_asset_info,
                 ask_asset_info,
+                ..
             } => (offer_asset_info.clone(), ask_asset_info.clone()),
         };
 
@@ -385,6 +473,7 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0001".to_string(),
             },
+            fee_tier: None,
         },
         SwapOperation::TerraSwap {
             offer_asset_info: AssetInfo::Token {
@@ -393,6 +482,7 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uluna".to_string(),
             },
+            fee_tier: None,
         }
     ])
     .is_ok());
@@ -406,6 +496,7 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0001".to_string(),
             },
+            fee_tier: None,
         },
         SwapOperation::TerraSwap {
             offer_asset_info: AssetInfo::Token {
@@ -414,6 +505,7 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uluna".to_string(),
             },
+            fee_tier: None,
         },
         SwapOperation::TerraSwap {
             offer_asset_info: AssetInfo::NativeToken {
@@ -422,6 +514,7 @@ fn test_invalid_operations() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0002".to_string(),
             },
+            fee_tier: None,
         },
     ])
     .is_ok());
@@ -13,6 +13,7 @@ use terraswap::router::SwapOperation;
 
 /// Execute swap operation
 /// swap all offer asset to ask asset
+#[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operation(
     deps: DepsMut,
     env: Env,
@@ -20,6 +21,8 @@ pub fn execute_swap_operation(
     operation: SwapOperation,
     to: Option<String>,
     deadline: Option<u64>,
+    referral_address: Option<String>,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<Response> {
     if env.contract.address != info.sender {
         return Err(StdError::generic_err("unauthorized"));
@@ -29,6 +32,7 @@ pub fn execute_swap_operation(
         SwapOperation::TerraSwap {
             offer_asset_info,
             ask_asset_info,
+            fee_tier,
         } => {
             let config: Config = CONFIG.load(deps.as_ref().storage)?;
             let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
@@ -36,6 +40,7 @@ pub fn execute_swap_operation(
                 &deps.querier,
                 terraswap_factory,
                 &[offer_asset_info.clone(), ask_asset_info],
+                fee_tier,
             )?;
 
             let amount = match offer_asset_info.clone() {
@@ -60,6 +65,8 @@ pub fn execute_swap_operation(
                 None,
                 to,
                 deadline,
+                referral_address,
+                referral_commission,
             )?]
         }
     };
@@ -67,6 +74,7 @@ pub fn execute_swap_operation(
     Ok(Response::new().add_messages(messages))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn asset_into_swap_msg(
     _deps: Deps,
     pair_contract: Addr,
@@ -74,6 +82,8 @@ pub fn asset_into_swap_msg(
     max_spread: Option<Decimal>,
     to: Option<String>,
     deadline: Option<u64>,
+    referral_address: Option<String>,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<CosmosMsg> {
     match offer_asset.info.clone() {
         AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -88,6 +98,8 @@ pub fn asset_into_swap_msg(
                 max_spread,
                 to,
                 deadline,
+                referral_address,
+                referral_commission,
             })?,
         })),
         AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -102,6 +114,8 @@ pub fn asset_into_swap_msg(
                     max_spread,
                     to,
                     deadline,
+                    referral_address,
+                    referral_commission,
                 })?,
             })?,
         })),
@@ -1,6 +1,7 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coin, from_binary, to_binary, Addr, Coin, CosmosMsg, StdError, SubMsg, Uint128, WasmMsg,
+    coin, from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, Decimal256, StdError, SubMsg,
+    Uint128, WasmMsg,
 };
 
 use crate::contract::{execute, instantiate, query};
@@ -8,8 +9,9 @@ use crate::operations::asset_into_swap_msg;
 use terraswap::mock_querier::mock_dependencies;
 
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use std::str::FromStr;
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::ExecuteMsg as PairExecuteMsg;
+use terraswap::pair::{ExecuteMsg as PairExecuteMsg, PriceResponse};
 use terraswap::router::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
     SimulateSwapOperationsResponse, SwapOperation,
@@ -56,6 +58,8 @@ fn execute_swap_operations() {
         minimum_receive: None,
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -74,6 +78,7 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0001".to_string(),
                 },
+                fee_tier: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -82,6 +87,7 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                fee_tier: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::NativeToken {
@@ -90,11 +96,14 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0002".to_string(),
                 },
+                fee_tier: None,
             },
         ],
         minimum_receive: Some(Uint128::from(1000000u128)),
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -113,9 +122,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0001".to_string(),
                         },
+                        fee_tier: None,
                     },
                     to: None,
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             })),
@@ -130,9 +142,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        fee_tier: None,
                     },
                     to: None,
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             })),
@@ -147,9 +162,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0002".to_string(),
                         },
+                        fee_tier: None,
                     },
                     to: Some("addr0000".to_string()),
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             })),
@@ -181,6 +199,7 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: "asset0001".to_string(),
                     },
+                    fee_tier: None,
                 },
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::Token {
@@ -189,6 +208,7 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
+                    fee_tier: None,
                 },
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::NativeToken {
@@ -197,11 +217,14 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: "asset0002".to_string(),
                     },
+                    fee_tier: None,
                 },
             ],
             minimum_receive: None,
             to: Some("addr0002".to_string()),
             deadline: None,
+            referral_address: None,
+            referral_commission: None,
         })
         .unwrap(),
     });
@@ -222,9 +245,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0001".to_string(),
                         },
+                        fee_tier: None,
                     },
                     to: None,
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             })),
@@ -239,9 +265,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        fee_tier: None,
                     },
                     to: None,
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             })),
@@ -256,9 +285,12 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0002".to_string(),
                         },
+                        fee_tier: None,
                     },
                     to: Some("addr0002".to_string()),
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             }))
@@ -266,6 +298,140 @@ fn execute_swap_operations() {
     );
 }
 
+#[test]
+fn execute_swap_operations_with_referral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            fee_tier: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            fee_tier: None,
+        },
+    ];
+
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        deadline: None,
+        referral_address: Some("referral0000".to_string()),
+        referral_commission: Some(Decimal::permille(1)),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the referral commission is only applied to the first operation
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[0].clone(),
+                    to: None,
+                    deadline: None,
+                    referral_address: Some("referral0000".to_string()),
+                    referral_commission: Some(Decimal::permille(1)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[1].clone(),
+                    to: Some("addr0000".to_string()),
+                    deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    deps.querier.with_terraswap_factory(
+        &[(
+            &"ukrwasset0001".to_string(),
+            &PairInfo {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: "asset0001".to_string(),
+                    },
+                ],
+                contract_addr: "pair0000".to_string(),
+                liquidity_token: "liquidity0000".to_string(),
+                asset_decimals: [6u8, 6u8],
+                fee_tier: 30,
+            },
+        )],
+        &[("ukrw".to_string(), 6u8)],
+    );
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![coin(1000000u128, "ukrw")],
+    )]);
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: operations[0].clone(),
+        to: None,
+        deadline: None,
+        referral_address: Some("referral0000".to_string()),
+        referral_commission: Some(Decimal::permille(1)),
+    };
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair0000".to_string(),
+            funds: vec![coin(1000000u128, "ukrw")],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+                deadline: None,
+                referral_address: Some("referral0000".to_string()),
+                referral_commission: Some(Decimal::permille(1)),
+            })
+            .unwrap(),
+        }))],
+    );
+}
+
 #[test]
 fn execute_swap_operation() {
     let mut deps = mock_dependencies(&[]);
@@ -293,6 +459,7 @@ fn execute_swap_operation() {
                 contract_addr: "pair0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [6u8, 6u8],
+                fee_tier: 30,
             },
         )],
         &[("uusd".to_string(), 6u8)],
@@ -314,9 +481,12 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            fee_tier: None,
         },
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
@@ -342,6 +512,8 @@ fn execute_swap_operation() {
                 None,
                 None,
                 None,
+                None,
+                None,
             )
             .unwrap()
         )],
@@ -357,9 +529,12 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            fee_tier: None,
         },
         to: Some("addr0000".to_string()),
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -378,6 +553,8 @@ fn execute_swap_operation() {
                 None,
                 Some("addr0000".to_string()),
                 None,
+                None,
+                None,
             )
             .unwrap()
         )],
@@ -397,6 +574,7 @@ fn execute_swap_operation() {
                 contract_addr: "pair0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [6u8, 6u8],
+                fee_tier: 30,
             },
         )],
         &[("uusd".to_string(), 6u8)],
@@ -414,9 +592,12 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
+            fee_tier: None,
         },
         to: Some("addr0000".to_string()),
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
 
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
@@ -440,6 +621,8 @@ fn execute_swap_operation() {
                     max_spread: None,
                     to: Some("addr0000".to_string()),
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap()
             })
@@ -448,6 +631,105 @@ fn execute_swap_operation() {
     );
 }
 
+#[test]
+fn execute_swap_operation_with_fee_tier() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+    ];
+    deps.querier.with_terraswap_factory(
+        &[
+            (
+                &"uusdasset0000".to_string(),
+                &PairInfo {
+                    asset_infos: asset_infos.clone(),
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: "liquidity0000".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    fee_tier: 30,
+                },
+            ),
+            (
+                &"uusdasset0000".to_string(),
+                &PairInfo {
+                    asset_infos: asset_infos.clone(),
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: "liquidity0001".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    fee_tier: 5,
+                },
+            ),
+        ],
+        &[("uusd".to_string(), 6u8)],
+    );
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        [Coin {
+            amount: Uint128::from(1000000u128),
+            denom: "uusd".to_string(),
+        }]
+        .to_vec(),
+    )]);
+
+    let swap_operation = |fee_tier: Option<u16>| ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::TerraSwap {
+            offer_asset_info: asset_infos[0].clone(),
+            ask_asset_info: asset_infos[1].clone(),
+            fee_tier,
+        },
+        to: None,
+        deadline: None,
+        referral_address: None,
+        referral_commission: None,
+    };
+
+    // the operation swaps through the pair of its fee tier
+    for (fee_tier, pair) in [
+        (None, "pair0000"),
+        (Some(30), "pair0000"),
+        (Some(5), "pair0001"),
+    ] {
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, swap_operation(fee_tier)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(
+                asset_into_swap_msg(
+                    deps.as_ref(),
+                    Addr::unchecked(pair),
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(1000000u128)
+                    },
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap()
+            )],
+        );
+    }
+
+    // no pair in the 100 bps tier
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operation(Some(100)));
+    assert!(res.is_err());
+}
+
 #[test]
 fn query_buy_with_routes() {
     let mut deps = mock_dependencies(&[]);
@@ -461,8 +743,111 @@ fn query_buy_with_routes() {
     // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            fee_tier: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            fee_tier: None,
+        },
+    ];
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000000u128),
+        operations: operations.clone(),
+        referral_commission: None,
+    };
+
+    deps.querier.with_terraswap_factory(
+        &[
+            (
+                &"ukrwasset0000".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0000".to_string(),
+                    liquidity_token: "liquidity0000".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    fee_tier: 30,
+                },
+            ),
+            (
+                &"asset0000uluna".to_string(),
+                &PairInfo {
+                    asset_infos: [
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    ],
+                    contract_addr: "pair0001".to_string(),
+                    liquidity_token: "liquidity0001".to_string(),
+                    asset_decimals: [6u8, 6u8],
+                    fee_tier: 30,
+                },
+            ),
+        ],
+        &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
+    );
+
+    let res: SimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1000000u128)
+        }
+    );
+
+    // the referral commission is taken from the first operation only
     let msg = QueryMsg::SimulateSwapOperations {
         offer_amount: Uint128::from(1000000u128),
+        operations,
+        referral_commission: Some(Decimal::percent(1)),
+    };
+    let res: SimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(990000u128)
+        }
+    );
+}
+
+#[test]
+fn query_price_with_routes() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = QueryMsg::Price {
         operations: vec![
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::NativeToken {
@@ -471,6 +856,7 @@ fn query_buy_with_routes() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
                 },
+                fee_tier: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -479,6 +865,7 @@ fn query_buy_with_routes() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                fee_tier: None,
             },
         ],
     };
@@ -499,6 +886,7 @@ fn query_buy_with_routes() {
                     contract_addr: "pair0000".to_string(),
                     liquidity_token: "liquidity0000".to_string(),
                     asset_decimals: [6u8, 6u8],
+                    fee_tier: 30,
                 },
             ),
             (
@@ -515,20 +903,28 @@ fn query_buy_with_routes() {
                     contract_addr: "pair0001".to_string(),
                     liquidity_token: "liquidity0001".to_string(),
                     asset_decimals: [6u8, 6u8],
+                    fee_tier: 30,
                 },
             ),
         ],
         &[("ukrw".to_string(), 6u8), ("uluna".to_string(), 6u8)],
     );
 
-    let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    // each pair quotes 1.5
+    let res: PriceResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
     assert_eq!(
         res,
-        SimulateSwapOperationsResponse {
-            amount: Uint128::from(1000000u128)
+        PriceResponse {
+            price: Decimal256::from_str("2.25").unwrap()
         }
     );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price { operations: vec![] },
+    );
+    assert_eq!(res, Err(StdError::generic_err("must provide operations")));
 }
 
 #[test]
@@ -569,6 +965,7 @@ fn query_reverse_routes_with_from_native() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            fee_tier: None,
         }],
     };
 
@@ -588,6 +985,7 @@ fn query_reverse_routes_with_from_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    fee_tier: 30,
                 },
             ),
             (
@@ -604,6 +1002,7 @@ fn query_reverse_routes_with_from_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    fee_tier: 30,
                 },
             ),
         ],
@@ -630,9 +1029,12 @@ fn query_reverse_routes_with_from_native() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
             },
+            fee_tier: None,
         },
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
     let info = mock_info("addr0", &[coin(offer_amount.u128(), "ukrw")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
@@ -660,6 +1062,8 @@ fn query_reverse_routes_with_from_native() {
                 max_spread: None,
                 to: None,
                 deadline: None,
+                referral_address: None,
+                referral_commission: None,
             })
             .unwrap(),
         })),],
@@ -701,6 +1105,7 @@ fn query_reverse_routes_with_to_native() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "ukrw".to_string(),
             },
+            fee_tier: None,
         }],
     };
 
@@ -720,6 +1125,7 @@ fn query_reverse_routes_with_to_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    fee_tier: 30,
                 },
             ),
             (
@@ -736,6 +1142,7 @@ fn query_reverse_routes_with_to_native() {
                         },
                     ],
                     asset_decimals: [8u8, 6u8],
+                    fee_tier: 30,
                 },
             ),
         ],
@@ -765,10 +1172,13 @@ fn query_reverse_routes_with_to_native() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "ukrw".to_string(),
                 },
+                fee_tier: None,
             }],
             minimum_receive: None,
             to: None,
             deadline: None,
+            referral_address: None,
+            referral_commission: None,
         })
         .unwrap(),
     });
@@ -788,9 +1198,12 @@ fn query_reverse_routes_with_to_native() {
                     ask_asset_info: AssetInfo::NativeToken {
                         denom: "ukrw".to_string(),
                     },
+                    fee_tier: None,
                 },
                 to: Some("addr0".to_string()),
                 deadline: None,
+                referral_address: None,
+                referral_commission: None,
             })
             .unwrap(),
         })),],
@@ -804,9 +1217,12 @@ fn query_reverse_routes_with_to_native() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "ukrw".to_string(),
             },
+            fee_tier: None,
         },
         to: None,
         deadline: None,
+        referral_address: None,
+        referral_commission: None,
     };
 
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
@@ -831,6 +1247,8 @@ fn query_reverse_routes_with_to_native() {
                     max_spread: None,
                     to: None,
                     deadline: None,
+                    referral_address: None,
+                    referral_commission: None,
                 })
                 .unwrap(),
             })
@@ -0,0 +1,568 @@
+use crate::error::ContractError;
+use crate::state::{
+    read_rewards, Config, RewardInfo, StakerReward, State, CONFIG, REWARDS, STAKERS,
+    STAKER_REWARDS, STATE,
+};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CanonicalAddr, CosmosMsg, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Response, StdResult, Storage, Uint128, Uint256, WasmMsg,
+};
+
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use std::convert::TryInto;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::staking::{
+    ConfigResponse, Cw20HookMsg, DistributionSchedule, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    PendingResponse, QueryMsg, RewardInfoResponse, StakerInfoResponse, StateResponse,
+};
+use terraswap::util::migrate_version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:terraswap-staking";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            staking_token: deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(&msg.staking_token)?.as_str())?,
+        },
+    )?;
+
+    STATE.save(
+        deps.storage,
+        &State {
+            total_bond_amount: Uint128::zero(),
+            last_distributed: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
+        ExecuteMsg::Withdraw {} => withdraw(deps, env, info),
+        ExecuteMsg::AddDistributionSchedule {
+            asset,
+            start_time,
+            end_time,
+        } => add_distribution_schedule(deps, env, info, asset, start_time, end_time),
+        ExecuteMsg::SweepUnclaimedRewards { asset_info } => {
+            sweep_unclaimed_rewards(deps, env, info, asset_info)
+        }
+        ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Bond {} => {
+            // only staking token contract can execute this message
+            let config: Config = CONFIG.load(deps.storage)?;
+            if deps.api.addr_canonicalize(info.sender.as_str())? != config.staking_token {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let staker = deps.api.addr_validate(&cw20_msg.sender)?;
+            bond(deps, env, staker, cw20_msg.amount)
+        }
+    }
+}
+
+pub fn bond(
+    deps: DepsMut,
+    env: Env,
+    staker: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let staker_raw = deps.api.addr_canonicalize(staker.as_str())?;
+    let mut state = update_rewards(deps.storage, env.block.time.seconds(), Some(&staker_raw))?;
+
+    let bond_amount = STAKERS
+        .may_load(deps.storage, staker_raw.as_slice())?
+        .unwrap_or_default();
+    STAKERS.save(
+        deps.storage,
+        staker_raw.as_slice(),
+        &bond_amount.checked_add(amount)?,
+    )?;
+
+    state.total_bond_amount = state.total_bond_amount.checked_add(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bond"),
+        ("staker", staker.as_str()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+pub fn unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let bond_amount = STAKERS
+        .may_load(deps.storage, staker_raw.as_slice())?
+        .unwrap_or_default();
+    if bond_amount < amount {
+        return Err(ContractError::InsufficientBondAmount {});
+    }
+
+    let mut state = update_rewards(deps.storage, env.block.time.seconds(), Some(&staker_raw))?;
+
+    let bond_amount = bond_amount - amount;
+    if bond_amount.is_zero() {
+        STAKERS.remove(deps.storage, staker_raw.as_slice());
+    } else {
+        STAKERS.save(deps.storage, staker_raw.as_slice(), &bond_amount)?;
+    }
+
+    state.total_bond_amount = state.total_bond_amount.checked_sub(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.staking_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            ("action", "unbond"),
+            ("staker", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let staker_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    update_rewards(deps.storage, env.block.time.seconds(), Some(&staker_raw))?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut withdrawn: Vec<String> = vec![];
+    for reward in read_rewards(deps.storage)? {
+        let key = (staker_raw.as_slice(), reward.info.as_bytes());
+        let mut staker_reward = match STAKER_REWARDS.may_load(deps.storage, key)? {
+            Some(staker_reward) => staker_reward,
+            None => continue,
+        };
+
+        if staker_reward.pending_reward.is_zero() {
+            continue;
+        }
+
+        let asset = Asset {
+            info: reward.info.to_normal(deps.api)?,
+            amount: staker_reward.pending_reward,
+        };
+
+        staker_reward.pending_reward = Uint128::zero();
+        STAKER_REWARDS.save(deps.storage, key, &staker_reward)?;
+
+        withdrawn.push(asset.to_string());
+        messages.push(asset.into_msg(info.sender.clone())?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw"),
+        ("staker", info.sender.as_str()),
+        ("rewards", &withdrawn.join(", ")),
+    ]))
+}
+
+// Only owner can execute it
+pub fn add_distribution_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Asset,
+    start_time: u64,
+    end_time: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    if start_time >= end_time {
+        return Err(ContractError::InvalidDistributionSchedule {
+            start_time,
+            end_time,
+        });
+    }
+
+    // the rewards before the last distribution could never be claimed
+    if start_time < env.block.time.seconds() {
+        return Err(ContractError::PastDistributionSchedule {});
+    }
+
+    let raw_info = asset.info.to_raw(deps.api)?;
+    if raw_info.as_bytes() == config.staking_token.as_slice() {
+        return Err(ContractError::StakingTokenReward {});
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    match &asset.info {
+        AssetInfo::NativeToken { .. } => asset.assert_sent_native_token_balance(&info)?,
+        AssetInfo::Token { contract_addr } => {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: asset.amount,
+                })?,
+                funds: vec![],
+            }));
+        }
+    }
+
+    // accrue the ongoing distributions before the new schedule takes effect
+    update_rewards(deps.storage, env.block.time.seconds(), None)?;
+
+    let mut reward = REWARDS
+        .may_load(deps.storage, raw_info.as_bytes())?
+        .unwrap_or(RewardInfo {
+            info: raw_info.clone(),
+            global_index: Decimal256::zero(),
+            distribution_schedules: vec![],
+            unclaimed_amount: Uint128::zero(),
+        });
+    reward.distribution_schedules.push(DistributionSchedule {
+        start_time,
+        end_time,
+        amount: asset.amount,
+    });
+    REWARDS.save(deps.storage, raw_info.as_bytes(), &reward)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "add_distribution_schedule"),
+        ("asset", &asset.to_string()),
+        ("start_time", &start_time.to_string()),
+        ("end_time", &end_time.to_string()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn sweep_unclaimed_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    update_rewards(deps.storage, env.block.time.seconds(), None)?;
+
+    let raw_info = asset_info.to_raw(deps.api)?;
+    let mut reward = REWARDS.load(deps.storage, raw_info.as_bytes())?;
+    if reward.unclaimed_amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let asset = Asset {
+        info: asset_info,
+        amount: reward.unclaimed_amount,
+    };
+
+    reward.unclaimed_amount = Uint128::zero();
+    REWARDS.save(deps.storage, raw_info.as_bytes(), &reward)?;
+
+    Ok(Response::new()
+        .add_message(asset.clone().into_msg(info.sender)?)
+        .add_attributes(vec![
+            ("action", "sweep_unclaimed_rewards"),
+            ("asset", &asset.to_string()),
+        ]))
+}
+
+// Only owner can execute it
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        // validate address format
+        let _ = deps.api.addr_validate(&owner)?;
+
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// Accrue the rewards distributed since the last distribution into the global indexes,
+/// and settle the pending rewards of the staker if it is given.
+fn update_rewards(
+    storage: &mut dyn Storage,
+    block_time: u64,
+    staker: Option<&CanonicalAddr>,
+) -> StdResult<State> {
+    let mut state: State = STATE.load(storage)?;
+    let bond_amount = match staker {
+        Some(staker) => STAKERS
+            .may_load(storage, staker.as_slice())?
+            .unwrap_or_default(),
+        None => Uint128::zero(),
+    };
+
+    let last_distributed = std::cmp::max(state.last_distributed, block_time);
+    for mut reward in read_rewards(storage)? {
+        compute_global_index(
+            &mut reward,
+            state.total_bond_amount,
+            state.last_distributed,
+            block_time,
+        );
+        // the finished schedules are fully distributed
+        reward
+            .distribution_schedules
+            .retain(|s| s.end_time > last_distributed);
+        REWARDS.save(storage, reward.info.as_bytes(), &reward)?;
+
+        if let Some(staker) = staker {
+            let key = (staker.as_slice(), reward.info.as_bytes());
+            let mut staker_reward = STAKER_REWARDS
+                .may_load(storage, key)?
+                .unwrap_or_else(default_staker_reward);
+            compute_staker_reward(&reward, &mut staker_reward, bond_amount)?;
+            STAKER_REWARDS.save(storage, key, &staker_reward)?;
+        }
+    }
+
+    state.last_distributed = last_distributed;
+    STATE.save(storage, &state)?;
+
+    Ok(state)
+}
+
+fn default_staker_reward() -> StakerReward {
+    StakerReward {
+        index: Decimal256::zero(),
+        pending_reward: Uint128::zero(),
+    }
+}
+
+/// Returns the amount distributed by the schedules between `from` and `to`
+fn compute_distributed_amount(schedules: &[DistributionSchedule], from: u64, to: u64) -> Uint128 {
+    schedules
+        .iter()
+        .map(|s| {
+            let start = std::cmp::max(s.start_time, from);
+            let end = std::cmp::min(s.end_time, to);
+            if start >= end {
+                Uint128::zero()
+            } else {
+                s.amount
+                    .multiply_ratio(end - start, s.end_time - s.start_time)
+            }
+        })
+        .sum()
+}
+
+/// The rewards distributed while nothing is bonded are left unclaimed for the owner
+fn compute_global_index(reward: &mut RewardInfo, total_bond_amount: Uint128, from: u64, to: u64) {
+    if from >= to {
+        return;
+    }
+
+    let distributed_amount = compute_distributed_amount(&reward.distribution_schedules, from, to);
+    if total_bond_amount.is_zero() {
+        reward.unclaimed_amount += distributed_amount;
+    } else {
+        reward.global_index += Decimal256::from_ratio(distributed_amount, total_bond_amount);
+    }
+}
+
+fn compute_staker_reward(
+    reward: &RewardInfo,
+    staker_reward: &mut StakerReward,
+    bond_amount: Uint128,
+) -> StdResult<()> {
+    let bond_amount: Uint256 = bond_amount.into();
+    let accrued_reward: Uint128 =
+        (bond_amount * (reward.global_index - staker_reward.index)).try_into()?;
+
+    staker_reward.pending_reward = staker_reward.pending_reward.checked_add(accrued_reward)?;
+    staker_reward.index = reward.global_index;
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::State {} => to_binary(&query_state(deps, env)?),
+        QueryMsg::StakerInfo { staker } => to_binary(&query_staker_info(deps, env, staker)?),
+        QueryMsg::Pending { staker } => to_binary(&query_pending(deps, env, staker)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let resp = ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        staking_token: deps.api.addr_humanize(&config.staking_token)?.to_string(),
+    };
+
+    Ok(resp)
+}
+
+pub fn query_state(deps: Deps, env: Env) -> StdResult<StateResponse> {
+    let state: State = STATE.load(deps.storage)?;
+    let block_time = env.block.time.seconds();
+
+    let rewards = read_rewards(deps.storage)?
+        .into_iter()
+        .map(|mut reward| {
+            compute_global_index(
+                &mut reward,
+                state.total_bond_amount,
+                state.last_distributed,
+                block_time,
+            );
+
+            Ok(RewardInfoResponse {
+                info: reward.info.to_normal(deps.api)?,
+                global_index: reward.global_index,
+                distribution_schedules: reward.distribution_schedules,
+                unclaimed_amount: reward.unclaimed_amount,
+            })
+        })
+        .collect::<StdResult<Vec<RewardInfoResponse>>>()?;
+
+    Ok(StateResponse {
+        total_bond_amount: state.total_bond_amount,
+        last_distributed: std::cmp::max(state.last_distributed, block_time),
+        rewards,
+    })
+}
+
+pub fn query_staker_info(deps: Deps, env: Env, staker: String) -> StdResult<StakerInfoResponse> {
+    let staker_raw = deps.api.addr_canonicalize(&staker)?;
+    let bond_amount = STAKERS
+        .may_load(deps.storage, staker_raw.as_slice())?
+        .unwrap_or_default();
+
+    Ok(StakerInfoResponse {
+        pending_rewards: compute_pending_rewards(deps, env, &staker_raw)?,
+        staker,
+        bond_amount,
+    })
+}
+
+pub fn query_pending(deps: Deps, env: Env, staker: String) -> StdResult<PendingResponse> {
+    let staker_raw = deps.api.addr_canonicalize(&staker)?;
+
+    Ok(PendingResponse {
+        rewards: compute_pending_rewards(deps, env, &staker_raw)?,
+    })
+}
+
+fn compute_pending_rewards(deps: Deps, env: Env, staker: &CanonicalAddr) -> StdResult<Vec<Asset>> {
+    let state: State = STATE.load(deps.storage)?;
+    let bond_amount = STAKERS
+        .may_load(deps.storage, staker.as_slice())?
+        .unwrap_or_default();
+
+    read_rewards(deps.storage)?
+        .into_iter()
+        .map(|mut reward| {
+            compute_global_index(
+                &mut reward,
+                state.total_bond_amount,
+                state.last_distributed,
+                env.block.time.seconds(),
+            );
+
+            let mut staker_reward = STAKER_REWARDS
+                .may_load(deps.storage, (staker.as_slice(), reward.info.as_bytes()))?
+                .unwrap_or_else(default_staker_reward);
+            compute_staker_reward(&reward, &mut staker_reward, bond_amount)?;
+
+            Ok(Asset {
+                info: reward.info.to_normal(deps.api)?,
+                amount: staker_reward.pending_reward,
+            })
+        })
+        .collect()
+}
+
+const TARGET_CONTRACT_VERSION: &str = "0.1.0";
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    migrate_version(
+        deps,
+        TARGET_CONTRACT_VERSION,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+
+    Ok(Response::default())
+}
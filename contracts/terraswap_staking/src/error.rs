@@ -0,0 +1,32 @@
+use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("{0}")]
+    ConversionOverflowError(#[from] ConversionOverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Cannot unbond more than bond amount")]
+    InsufficientBondAmount {},
+
+    #[error("Invalid distribution schedule ({start_time} >= {end_time})")]
+    InvalidDistributionSchedule { start_time: u64, end_time: u64 },
+
+    #[error("Distribution schedule must not start in the past")]
+    PastDistributionSchedule {},
+
+    #[error("Staking token cannot be used as a reward")]
+    StakingTokenReward {},
+}
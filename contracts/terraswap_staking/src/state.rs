@@ -0,0 +1,58 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Decimal256, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use terraswap::asset::AssetInfoRaw;
+use terraswap::staking::DistributionSchedule;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub staking_token: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct State {
+    pub total_bond_amount: Uint128,
+    pub last_distributed: u64,
+}
+
+pub const STATE: Item<State> = Item::new("state");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RewardInfo {
+    pub info: AssetInfoRaw,
+    pub global_index: Decimal256,
+    pub distribution_schedules: Vec<DistributionSchedule>,
+    /// Distributed while nothing was bonded, the owner can sweep it
+    #[serde(default)]
+    pub unclaimed_amount: Uint128,
+}
+
+// key : reward asset info / value: reward distribution info
+pub const REWARDS: Map<&[u8], RewardInfo> = Map::new("rewards");
+
+pub fn read_rewards(storage: &dyn Storage) -> StdResult<Vec<RewardInfo>> {
+    REWARDS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(v)
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StakerReward {
+    pub index: Decimal256,
+    pub pending_reward: Uint128,
+}
+
+// key : staker / value: bond amount
+pub const STAKERS: Map<&[u8], Uint128> = Map::new("stakers");
+
+// key : (staker, reward asset info) / value: staker reward
+pub const STAKER_REWARDS: Map<(&[u8], &[u8]), StakerReward> = Map::new("staker_rewards");
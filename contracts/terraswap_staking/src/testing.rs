@@ -0,0 +1,611 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use terraswap::mock_querier::mock_dependencies;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal256, Env, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::staking::{
+    ConfigResponse, Cw20HookMsg, DistributionSchedule, ExecuteMsg, InstantiateMsg, PendingResponse,
+    QueryMsg, RewardInfoResponse, StakerInfoResponse, StateResponse,
+};
+
+fn env_after(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn bond_msg(staker: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: staker.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+    })
+}
+
+fn native_reward(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        amount: Uint128::from(amount),
+    }
+}
+
+fn query_pending(deps: cosmwasm_std::Deps, env: Env, staker: &str) -> Vec<Asset> {
+    let res: PendingResponse = from_binary(
+        &query(
+            deps,
+            env,
+            QueryMsg::Pending {
+                staker: staker.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    res.rewards
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            staking_token: "liquidity0000".to_string(),
+        }
+    );
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(
+        state,
+        StateResponse {
+            total_bond_amount: Uint128::zero(),
+            last_distributed: mock_env().block.time.seconds(),
+            rewards: vec![],
+        }
+    );
+}
+
+#[test]
+fn bond_and_unbond() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only staking token can bond
+    let info = mock_info("liquidity0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, bond_msg("addr0000", 100));
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("liquidity0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, bond_msg("addr0000", 100)).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("staker", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let staker_info: StakerInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StakerInfo {
+                staker: "addr0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(staker_info.bond_amount, Uint128::from(100u128));
+
+    // cannot unbond more than bonded
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Unbond {
+            amount: Uint128::from(101u128),
+        },
+    );
+    assert_eq!(res, Err(ContractError::InsufficientBondAmount {}));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Unbond {
+            amount: Uint128::from(100u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "liquidity0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(state.total_bond_amount, Uint128::zero());
+}
+
+#[test]
+fn add_distribution_schedule() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    let msg = ExecuteMsg::AddDistributionSchedule {
+        asset: native_reward(1_000),
+        start_time,
+        end_time: start_time + 100,
+    };
+
+    // only owner can add schedule
+    let info = mock_info("addr0000", &coins(1_000, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // native reward must be sent
+    let info = mock_info("owner0000", &coins(999, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert!(res.is_err());
+
+    // invalid schedule
+    let info = mock_info("owner0000", &coins(1_000, "uluna"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::AddDistributionSchedule {
+            asset: native_reward(1_000),
+            start_time,
+            end_time: start_time,
+        },
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidDistributionSchedule {
+            start_time,
+            end_time: start_time,
+        })
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::AddDistributionSchedule {
+            asset: native_reward(1_000),
+            start_time: start_time - 1,
+            end_time: start_time + 100,
+        },
+    );
+    assert_eq!(res, Err(ContractError::PastDistributionSchedule {}));
+
+    // staking token cannot be a reward
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::AddDistributionSchedule {
+            asset: Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "liquidity0000".to_string(),
+                },
+                amount: Uint128::from(1_000u128),
+            },
+            start_time,
+            end_time: start_time + 100,
+        },
+    );
+    assert_eq!(res, Err(ContractError::StakingTokenReward {}));
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    // cw20 reward is transferred from the owner
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AddDistributionSchedule {
+            asset: Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "reward0000".to_string(),
+                },
+                amount: Uint128::from(500u128),
+            },
+            start_time: start_time + 100,
+            end_time: start_time + 200,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "owner0000".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::from(500u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap()).unwrap();
+    // rewards are ordered by the raw asset info
+    assert_eq!(
+        state.rewards,
+        vec![
+            RewardInfoResponse {
+                info: AssetInfo::Token {
+                    contract_addr: "reward0000".to_string(),
+                },
+                global_index: Decimal256::zero(),
+                distribution_schedules: vec![DistributionSchedule {
+                    start_time: start_time + 100,
+                    end_time: start_time + 200,
+                    amount: Uint128::from(500u128),
+                }],
+                unclaimed_amount: Uint128::zero(),
+            },
+            RewardInfoResponse {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                global_index: Decimal256::zero(),
+                distribution_schedules: vec![DistributionSchedule {
+                    start_time,
+                    end_time: start_time + 100,
+                    amount: Uint128::from(1_000u128),
+                }],
+                unclaimed_amount: Uint128::zero(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn distribute_rewards_to_stakers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    let info = mock_info("owner0000", &coins(1_000, "uluna"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AddDistributionSchedule {
+            asset: native_reward(1_000),
+            start_time,
+            end_time: start_time + 100,
+        },
+    )
+    .unwrap();
+
+    // addr0000 bonds alone for the first 50 seconds
+    let info = mock_info("liquidity0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        bond_msg("addr0000", 100),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(50), "addr0000"),
+        vec![native_reward(500)]
+    );
+
+    // addr0001 bonds three times more than addr0000
+    execute(
+        deps.as_mut(),
+        env_after(50),
+        info,
+        bond_msg("addr0001", 300),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(100), "addr0000"),
+        vec![native_reward(625)]
+    );
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(100), "addr0001"),
+        vec![native_reward(375)]
+    );
+
+    // the schedule is over
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(1_000), "addr0000"),
+        vec![native_reward(625)]
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_after(1_000),
+        info,
+        ExecuteMsg::Withdraw {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(625, "uluna"),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("staker", "addr0000"),
+            attr("rewards", "625uluna"),
+        ]
+    );
+
+    // nothing left to withdraw
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(1_000), "addr0000"),
+        vec![native_reward(0)]
+    );
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_after(1_000),
+        info,
+        ExecuteMsg::Withdraw {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    // unbond keeps the pending rewards
+    let info = mock_info("addr0001", &[]);
+    execute(
+        deps.as_mut(),
+        env_after(1_000),
+        info,
+        ExecuteMsg::Unbond {
+            amount: Uint128::from(300u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(2_000), "addr0001"),
+        vec![native_reward(375)]
+    );
+}
+
+#[test]
+fn prune_finished_schedules() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    for i in 0..2 {
+        let info = mock_info("owner0000", &coins(1_000, "uluna"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AddDistributionSchedule {
+                asset: native_reward(1_000),
+                start_time: start_time + i * 100,
+                end_time: start_time + (i + 1) * 100,
+            },
+        )
+        .unwrap();
+    }
+
+    let info = mock_info("liquidity0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, bond_msg("addr0000", 100)).unwrap();
+
+    let query_schedules = |deps: cosmwasm_std::Deps, env: Env| {
+        let state: StateResponse =
+            from_binary(&query(deps, env, QueryMsg::State {}).unwrap()).unwrap();
+        state.rewards[0].distribution_schedules.clone()
+    };
+
+    // the first schedule is dropped once it is accrued
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        env_after(150),
+        info.clone(),
+        ExecuteMsg::Withdraw {},
+    )
+    .unwrap();
+    assert_eq!(
+        query_schedules(deps.as_ref(), env_after(150)),
+        vec![DistributionSchedule {
+            start_time: start_time + 100,
+            end_time: start_time + 200,
+            amount: Uint128::from(1_000u128),
+        }]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env_after(1_000),
+        info,
+        ExecuteMsg::Withdraw {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("staker", "addr0000"),
+            attr("rewards", "500uluna"),
+        ]
+    );
+    assert_eq!(query_schedules(deps.as_ref(), env_after(1_000)), vec![]);
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(2_000), "addr0000"),
+        vec![native_reward(0)]
+    );
+}
+
+#[test]
+fn sweep_unclaimed_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    let info = mock_info("owner0000", &coins(1_000, "uluna"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AddDistributionSchedule {
+            asset: native_reward(1_000),
+            start_time,
+            end_time: start_time + 100,
+        },
+    )
+    .unwrap();
+
+    // nothing is bonded for the first 40 seconds
+    let info = mock_info("liquidity0000", &[]);
+    execute(
+        deps.as_mut(),
+        env_after(40),
+        info,
+        bond_msg("addr0000", 100),
+    )
+    .unwrap();
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), env_after(100), QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(state.rewards[0].unclaimed_amount, Uint128::from(400u128));
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(100), "addr0000"),
+        vec![native_reward(600)]
+    );
+
+    let msg = ExecuteMsg::SweepUnclaimedRewards {
+        asset_info: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+    };
+
+    // only owner can sweep
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env_after(100), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), env_after(100), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "owner0000".to_string(),
+            amount: coins(400, "uluna"),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep_unclaimed_rewards"),
+            attr("asset", "400uluna"),
+        ]
+    );
+
+    let res = execute(deps.as_mut(), env_after(100), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidZeroAmount {}));
+
+    // the stakers keep their rewards
+    assert_eq!(
+        query_pending(deps.as_ref(), env_after(100), "addr0000"),
+        vec![native_reward(600)]
+    );
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        staking_token: "liquidity0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("owner0001".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.owner, "owner0001".to_string());
+}
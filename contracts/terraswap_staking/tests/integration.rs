@@ -0,0 +1,415 @@
+use cosmwasm_std::{coin, coins, to_binary, Addr, Coin, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use terraswap::asset::{Asset, AssetInfo, PairInfo};
+use terraswap::factory::InstantiateMsg as FactoryInstantiateMsg;
+use terraswap::pair::{
+    ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg, QueryMsg as PairQueryMsg,
+};
+use terraswap::staking::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PendingResponse, QueryMsg, StakerInfoResponse,
+};
+use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+
+const OWNER: &str = "owner0000";
+const USER: &str = "addr0000";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_token::contract::execute,
+        terraswap_token::contract::instantiate,
+        terraswap_token::contract::query,
+    ))
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_factory::contract::execute,
+            terraswap_factory::contract::instantiate,
+            terraswap_factory::contract::query,
+        )
+        .with_reply(terraswap_factory::contract::reply),
+    )
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            terraswap_pair::contract::execute,
+            terraswap_pair::contract::instantiate,
+            terraswap_pair::contract::query,
+        )
+        .with_reply(terraswap_pair::contract::reply),
+    )
+}
+
+fn staking_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        terraswap_staking::contract::execute,
+        terraswap_staking::contract::instantiate,
+        terraswap_staking::contract::query,
+    ))
+}
+
+fn query_token_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    res.balance
+}
+
+struct Suite {
+    app: App,
+    token: Addr,
+    liquidity_token: Addr,
+    staking: Addr,
+}
+
+/// Creates an uusd-token pair, provides liquidity from USER
+/// and instantiates the staking contract for the LP token
+fn setup() -> Suite {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &Addr::unchecked(USER),
+                coins(1_000_000_000, "uusd"),
+            )
+            .unwrap();
+        router
+            .bank
+            .init_balance(
+                storage,
+                &Addr::unchecked(OWNER),
+                coins(1_000_000_000, "uluna"),
+            )
+            .unwrap();
+    });
+
+    let token_code_id = app.store_code(token_contract());
+    let pair_code_id = app.store_code(pair_contract());
+    let factory_code_id = app.store_code(factory_contract());
+    let staking_code_id = app.store_code(staking_contract());
+
+    let token = app
+        .instantiate_contract(
+            token_code_id,
+            Addr::unchecked(OWNER),
+            &TokenInstantiateMsg {
+                name: "terraswap token".to_string(),
+                symbol: "TKN".to_string(),
+                decimals: 6,
+                initial_balances: vec![
+                    Cw20Coin {
+                        address: USER.to_string(),
+                        amount: Uint128::from(1_000_000_000u128),
+                    },
+                    Cw20Coin {
+                        address: OWNER.to_string(),
+                        amount: Uint128::from(1_000_000_000u128),
+                    },
+                ],
+                mint: Some(MinterResponse {
+                    minter: OWNER.to_string(),
+                    cap: None,
+                }),
+            },
+            &[],
+            "token",
+            None,
+        )
+        .unwrap();
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: token.to_string(),
+        },
+    ];
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(OWNER),
+            &FactoryInstantiateMsg {
+                pair_code_id,
+                token_code_id,
+                timelock_delay: 0,
+            },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    // the pair checks the pause flag of its factory on deposits
+    let pair = app
+        .instantiate_contract(
+            pair_code_id,
+            factory,
+            &PairInstantiateMsg {
+                asset_infos: asset_infos.clone(),
+                token_code_id,
+                asset_decimals: [6u8, 6u8],
+                fee_tier: None,
+            },
+            &[],
+            "pair",
+            None,
+        )
+        .unwrap();
+
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(&pair, &PairQueryMsg::Pair {})
+        .unwrap();
+    let liquidity_token = Addr::unchecked(pair_info.liquidity_token);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        token.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: pair.to_string(),
+            amount: Uint128::from(1_000_000u128),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        pair,
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1_000_000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(1_000_000u128),
+                },
+            ],
+            receiver: None,
+            deadline: None,
+            slippage_tolerance: None,
+        },
+        &coins(1_000_000, "uusd"),
+    )
+    .unwrap();
+
+    let staking = app
+        .instantiate_contract(
+            staking_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                staking_token: liquidity_token.to_string(),
+            },
+            &[],
+            "staking",
+            None,
+        )
+        .unwrap();
+
+    Suite {
+        app,
+        token,
+        liquidity_token,
+        staking,
+    }
+}
+
+#[test]
+fn bond_lp_tokens_and_claim_rewards() {
+    let Suite {
+        mut app,
+        token,
+        liquidity_token,
+        staking,
+    } = setup();
+
+    // 1_000_000 - MINIMUM_LIQUIDITY_AMOUNT
+    let lp_amount = Uint128::from(999_000u128);
+    assert_eq!(query_token_balance(&app, &liquidity_token, USER), lp_amount);
+
+    let start_time = app.block_info().time.seconds();
+
+    // native reward
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        staking.clone(),
+        &ExecuteMsg::AddDistributionSchedule {
+            asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::from(1_000_000u128),
+            },
+            start_time,
+            end_time: start_time + 1_000,
+        },
+        &coins(1_000_000, "uluna"),
+    )
+    .unwrap();
+
+    // cw20 reward
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: staking.to_string(),
+            amount: Uint128::from(2_000_000u128),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        staking.clone(),
+        &ExecuteMsg::AddDistributionSchedule {
+            asset: Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token.to_string(),
+                },
+                amount: Uint128::from(2_000_000u128),
+            },
+            start_time,
+            end_time: start_time + 1_000,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_token_balance(&app, &token, staking.as_str()),
+        Uint128::from(2_000_000u128)
+    );
+
+    // the pair token cannot be bonded
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: staking.to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        liquidity_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: staking.to_string(),
+            amount: lp_amount,
+            msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_token_balance(&app, &liquidity_token, staking.as_str()),
+        lp_amount
+    );
+
+    // half of the schedules has passed
+    app.update_block(|b| b.time = b.time.plus_seconds(500));
+
+    let pending: PendingResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::Pending {
+                staker: USER.to_string(),
+            },
+        )
+        .unwrap();
+    // the rewards are rounded down by the global index precision
+    assert_eq!(
+        pending.rewards,
+        vec![
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token.to_string(),
+                },
+                amount: Uint128::from(999_999u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::from(499_999u128),
+            },
+        ]
+    );
+
+    let token_balance = query_token_balance(&app, &token, USER);
+    app.execute_contract(
+        Addr::unchecked(USER),
+        staking.clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        app.wrap().query_balance(USER, "uluna").unwrap(),
+        coin(499_999, "uluna")
+    );
+    assert_eq!(
+        query_token_balance(&app, &token, USER),
+        token_balance + Uint128::from(999_999u128)
+    );
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        staking.clone(),
+        &ExecuteMsg::Unbond { amount: lp_amount },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_token_balance(&app, &liquidity_token, USER), lp_amount);
+
+    // nothing is accrued without bond
+    app.update_block(|b| b.time = b.time.plus_seconds(500));
+
+    let staker_info: StakerInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::StakerInfo {
+                staker: USER.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(staker_info.bond_amount, Uint128::zero());
+    assert!(staker_info
+        .pending_rewards
+        .iter()
+        .all(|asset| asset.amount.is_zero()));
+
+    // the rewards left are kept in the staking contract
+    assert_eq!(
+        app.wrap().query_balance(&staking, "uluna").unwrap(),
+        Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::from(500_001u128),
+        }
+    );
+}
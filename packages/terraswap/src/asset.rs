@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 use crate::querier::{query_balance, query_native_decimals, query_token_balance, query_token_info};
+use crate::util::default_fee_tier;
 use cosmwasm_std::{
     to_binary, Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, MessageInfo, QuerierWrapper,
     StdError, StdResult, SubMsg, Uint128, WasmMsg,
@@ -174,6 +175,17 @@ impl AssetInfo {
             }
         }
     }
+
+    /// The denom of a native token, the symbol of a cw20 token
+    pub fn query_symbol(&self, querier: &QuerierWrapper) -> StdResult<String> {
+        match self {
+            AssetInfo::NativeToken { denom } => Ok(denom.to_string()),
+            AssetInfo::Token { contract_addr } => {
+                let token_info = query_token_info(querier, Addr::unchecked(contract_addr))?;
+                Ok(token_info.symbol)
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -252,6 +264,9 @@ pub struct PairInfo {
     pub contract_addr: String,
     pub liquidity_token: String,
     pub asset_decimals: [u8; 2],
+    /// Commission rate in basis points
+    #[serde(default = "default_fee_tier")]
+    pub fee_tier: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -260,6 +275,8 @@ pub struct PairInfoRaw {
     pub contract_addr: CanonicalAddr,
     pub liquidity_token: CanonicalAddr,
     pub asset_decimals: [u8; 2],
+    #[serde(default = "default_fee_tier")]
+    pub fee_tier: u16,
 }
 
 impl PairInfoRaw {
@@ -272,6 +289,7 @@ impl PairInfoRaw {
                 self.asset_infos[1].to_normal(api)?,
             ],
             asset_decimals: self.asset_decimals,
+            fee_tier: self.fee_tier,
         })
     }
 
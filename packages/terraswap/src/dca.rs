@@ -0,0 +1,94 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::Asset;
+use crate::router::SwapOperation;
+
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub terraswap_router: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Deposit a native `offer_asset` which is swapped by
+    /// `amount_per_execution` every `interval` seconds
+    CreateSchedule {
+        offer_asset: Asset,
+        interval: u64,
+        amount_per_execution: Uint128,
+        operations: Vec<SwapOperation>,
+        /// Minimum return of an `amount_per_execution` swap,
+        /// the last smaller swap is checked against its share.
+        /// Required as anyone can trigger the swaps
+        minimum_receive: Uint128,
+    },
+    /// The owner cancels the schedule and gets the remaining deposit back
+    CancelSchedule {
+        id: u64,
+    },
+    /// Anyone can execute the next swap of the schedule once the interval has elapsed
+    Trigger {
+        id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    CreateSchedule {
+        interval: u64,
+        amount_per_execution: Uint128,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Schedule {
+        id: u64,
+    },
+    /// Schedules of the owner ordered by id
+    Schedules {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub terraswap_router: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ScheduleResponse {
+    pub id: u64,
+    pub owner: String,
+    /// The remaining deposit
+    pub offer_asset: Asset,
+    pub interval: u64,
+    pub amount_per_execution: Uint128,
+    pub operations: Vec<SwapOperation>,
+    pub minimum_receive: Uint128,
+    /// Block time in seconds from which the schedule can be triggered
+    pub next_execution: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SchedulesResponse {
+    pub schedules: Vec<ScheduleResponse>,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
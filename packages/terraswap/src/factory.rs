@@ -3,58 +3,265 @@ use serde::{Deserialize, Serialize};
 
 use crate::asset::{Asset, AssetInfo, PairInfo};
 
+use cosmwasm_std::{Decimal, Uint128};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct InstantiateMsg {
     /// Pair contract code ID, which is used to
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    /// Seconds a config change or a pair migration waits in the queue
+    pub timelock_delay: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// UpdateConfig update relevant code IDs
+    /// UpdateConfig queues an update of relevant code IDs
     UpdateConfig {
-        owner: Option<String>,
         token_code_id: Option<u64>,
         pair_code_id: Option<u64>,
+        /// Maximum referral commission rate the pairs accept on swaps
+        max_referral_commission: Option<Decimal>,
+        timelock_delay: Option<u64>,
+        /// Fee charged on CreatePair, a zero amount removes it
+        creation_fee: Option<Asset>,
+        /// Receives the creation fees, the owner when unset
+        fee_recipient: Option<String>,
+        /// Anyone can create pairs, otherwise only the owner and the allowed creators
+        permissionless: Option<bool>,
+        /// Pairs can only be created with the allowed assets
+        restrict_assets: Option<bool>,
+        /// The account allowed to pause the factory next to the owner,
+        /// an empty string removes it
+        guardian: Option<String>,
     },
-    /// CreatePair instantiates pair contract
+    /// CreatePair instantiates pair contract, the creation fee must be sent along
+    /// or approved when it is a cw20 token
     CreatePair {
         assets: [Asset; 2],
+        /// Commission rate in basis points, one of 1, 5, 30 and 100. 30 when unset
+        fee_tier: Option<u16>,
+        /// Overrides the generated `terraswap pair {symbol}-{symbol}` contract label
+        label: Option<String>,
     },
     AddNativeTokenDecimals {
         denom: String,
         decimals: u8,
     },
+    /// UpdateCreators changes the accounts allowed to create pairs
+    /// while the creation is permissioned
+    UpdateCreators {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// UpdateAllowedAssets changes the assets pairs can be created with
+    /// while the assets are restricted
+    UpdateAllowedAssets {
+        add: Vec<AssetInfo>,
+        remove: Vec<AssetInfo>,
+    },
+    /// UpdateBlacklist changes the assets pairs cannot be created with
+    UpdateBlacklist {
+        add: Vec<AssetInfo>,
+        remove: Vec<AssetInfo>,
+    },
+    /// UpdatePairMetadata sets the metadata of a registered pair, None removes it
+    UpdatePairMetadata {
+        asset_infos: [AssetInfo; 2],
+        fee_tier: Option<u16>,
+        metadata: Option<PairMetadata>,
+    },
+    /// UpdateAssetMetadata sets the metadata of an asset, None removes it
+    UpdateAssetMetadata {
+        asset_info: AssetInfo,
+        metadata: Option<AssetMetadata>,
+    },
+    /// Pause blocks pair creation and the swaps and deposits of all pairs,
+    /// the owner or the guardian can execute it
+    Pause {},
+    /// Unpause lifts the pause, only the owner can execute it
+    Unpause {},
+    /// DeregisterPair removes the pair from the registry,
+    /// it stays queryable with DeregisteredPairs
+    DeregisterPair {
+        asset_infos: [AssetInfo; 2],
+        fee_tier: Option<u16>,
+    },
+    /// MigratePair queues a migration of the pair
     MigratePair {
         contract: String,
         code_id: Option<u64>,
     },
+    /// MigratePairs queues a migration of a page of the registered pairs
+    MigratePairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+        code_id: Option<u64>,
+    },
+    /// BackfillIndexes indexes a page of the pairs registered before
+    /// the pair indexes, only the owner can execute it
+    BackfillIndexes {
+        limit: Option<u32>,
+    },
+    /// Anyone can execute a queued action once its eta has passed
+    ExecuteAction {
+        id: u64,
+    },
+    /// The owner drops a queued action
+    CancelAction {
+        id: u64,
+    },
+    /// The owner proposes a new owner, who has `expires_in` seconds to accept it
+    ProposeNewOwner {
+        owner: String,
+        expires_in: u64,
+    },
+    /// The proposed owner takes over the factory
+    AcceptOwnership {},
+    /// The owner drops the pending proposal
+    DropOwnershipProposal {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairMetadata {
+    pub verified: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AssetMetadata {
+    pub symbol: String,
+    pub name: String,
+    pub logo_url: Option<String>,
+    /// Only for native tokens, cw20 tokens expose their own decimals
+    pub decimals: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockAction {
+    UpdateConfig {
+        token_code_id: Option<u64>,
+        pair_code_id: Option<u64>,
+        max_referral_commission: Option<Decimal>,
+        timelock_delay: Option<u64>,
+        creation_fee: Option<Asset>,
+        fee_recipient: Option<String>,
+        permissionless: Option<bool>,
+        restrict_assets: Option<bool>,
+        guardian: Option<String>,
+    },
+    MigratePair {
+        contract: String,
+        code_id: u64,
+    },
+    MigratePairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+        code_id: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// The pair in the fee tier, the default tier when unset
     Pair {
         asset_infos: [AssetInfo; 2],
+        fee_tier: Option<u16>,
     },
+    /// The pairs of the assets in all fee tiers
+    PairsForAssets {
+        asset_infos: [AssetInfo; 2],
+    },
+    /// Pages hold all the fee tiers of their last asset pair,
+    /// so the next page starts after all of them
     Pairs {
         start_after: Option<[AssetInfo; 2]>,
         limit: Option<u32>,
+        /// Returns the metadata of each pair and of its assets along with the pairs
+        #[serde(default)]
+        with_metadata: Option<bool>,
     },
     NativeTokenDecimals {
         denom: String,
     },
+    PendingActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    PairByAddress {
+        contract_addr: String,
+    },
+    PairByLiquidityToken {
+        liquidity_token: String,
+    },
+    /// Pairs containing the asset
+    PairsByAsset {
+        asset_info: AssetInfo,
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Pairs which are not on the current pair_code_id among a page of
+    /// the registered pairs, in the order of the Pairs query
+    OutdatedPairs {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    Creators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllowedAssets {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+    BlacklistedAssets {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+    /// Pairs removed from the registry, ordered by contract address
+    DeregisteredPairs {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Pairs along with their reserves and total LP supply
+    PairsWithPools {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    PairsMetadata {
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    AssetsMetadata {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+    /// Address the pair of the assets is instantiated at with the current pair_code_id
+    PredictPairAddress {
+        asset_infos: [AssetInfo; 2],
+        fee_tier: Option<u16>,
+    },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub pending_owner: Option<String>,
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    pub max_referral_commission: Decimal,
+    pub timelock_delay: u64,
+    pub creation_fee: Option<Asset>,
+    pub fee_recipient: Option<String>,
+    pub permissionless: bool,
+    pub restrict_assets: bool,
+    pub guardian: Option<String>,
+    pub paused: bool,
 }
 
 /// We currently take no arguments for migrations
@@ -65,9 +272,96 @@ pub struct MigrateMsg {}
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
+    /// Metadata of the pairs in the order of `pairs`, only when requested
+    #[serde(default)]
+    pub metadata: Option<Vec<PairInfoMetadata>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OutdatedPairsResponse {
+    pub pairs: Vec<PairInfo>,
+    /// The last scanned pair, the next page starts after it.
+    /// None once all the pairs are scanned
+    pub last_scanned: Option<[AssetInfo; 2]>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct NativeTokenDecimalsResponse {
     pub decimals: u8,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingActionResponse {
+    pub id: u64,
+    pub action: TimelockAction,
+    /// The action can be executed from this time, in seconds
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingActionsResponse {
+    pub actions: Vec<PendingActionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CreatorsResponse {
+    pub creators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AllowedAssetsResponse {
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct BlacklistedAssetsResponse {
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairWithPool {
+    pub pair: PairInfo,
+    /// Reserves, in the order of `pair.asset_infos`
+    pub assets: [Asset; 2],
+    pub total_share: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairsWithPoolsResponse {
+    pub pairs: Vec<PairWithPool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairMetadataResponse {
+    pub asset_infos: [AssetInfo; 2],
+    pub fee_tier: u16,
+    pub metadata: PairMetadata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairsMetadataResponse {
+    pub pairs: Vec<PairMetadataResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AssetMetadataResponse {
+    pub asset_info: AssetInfo,
+    pub metadata: AssetMetadata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AssetsMetadataResponse {
+    pub assets: Vec<AssetMetadataResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairInfoMetadata {
+    pub metadata: Option<PairMetadata>,
+    /// Metadata of the assets, in the order of the pair asset infos
+    pub asset_metadata: [Option<AssetMetadata>; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairAddressResponse {
+    pub contract_addr: String,
+}
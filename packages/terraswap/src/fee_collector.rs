@@ -0,0 +1,92 @@
+use cosmwasm_std::Decimal;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::AssetInfo;
+use crate::router::SwapOperation;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub terraswap_router: String,
+    /// All collected assets are converted into the target asset
+    pub target_asset_info: AssetInfo,
+    pub beneficiaries: Vec<Beneficiary>,
+    /// Maximum spread below the route belief price accepted on collection
+    pub max_spread: Decimal,
+}
+
+/// Beneficiary receives `weight / total weight` of the converted target asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Beneficiary {
+    pub address: String,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Swap the whole balance of the given assets into the target asset
+    /// through the configured routes and distribute it to the beneficiaries
+    Collect {
+        assets: Vec<AssetInfo>,
+    },
+    /// Owner registers the router operations which convert
+    /// `asset_info` into the target asset
+    UpdateRoute {
+        asset_info: AssetInfo,
+        operations: Vec<SwapOperation>,
+        /// Expected price of the target asset in `asset_info`
+        belief_price: Decimal,
+    },
+    RemoveRoute {
+        asset_info: AssetInfo,
+    },
+    UpdateConfig {
+        owner: Option<String>,
+        beneficiaries: Option<Vec<Beneficiary>>,
+        max_spread: Option<Decimal>,
+    },
+
+    /// Internal use
+    /// Distribute the target asset balance to the beneficiaries
+    Distribute {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Route {
+        asset_info: AssetInfo,
+    },
+    Routes {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub terraswap_router: String,
+    pub target_asset_info: AssetInfo,
+    pub beneficiaries: Vec<Beneficiary>,
+    pub max_spread: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RouteResponse {
+    pub asset_info: AssetInfo,
+    pub operations: Vec<SwapOperation>,
+    pub belief_price: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RoutesResponse {
+    pub routes: Vec<RouteResponse>,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
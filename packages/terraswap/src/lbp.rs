@@ -0,0 +1,91 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::{Decimal, Decimal256};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The only account allowed to deposit and withdraw the pool assets
+    pub owner: String,
+    pub asset_infos: [AssetInfo; 2],
+    /// Weights at `start_time`, only their ratio matters
+    pub start_weights: [Decimal256; 2],
+    /// Weights from `end_time` on, only their ratio matters
+    pub end_weights: [Decimal256; 2],
+    /// Swaps are enabled from `start_time`, in seconds
+    pub start_time: u64,
+    /// The weights move linearly until `end_time`, in seconds
+    pub end_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// The owner deposits the assets, cw20 tokens need an allowance
+    ProvideLiquidity {
+        assets: [Asset; 2],
+    },
+    /// The owner takes out all the assets while the sale is not running
+    WithdrawLiquidity {},
+    Swap {
+        offer_asset: Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+        deadline: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+        deadline: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Pool {},
+    /// Weights at the current block time
+    Weights {},
+    Simulation {
+        offer_asset: Asset,
+    },
+    ReverseSimulation {
+        ask_asset: Asset,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub asset_infos: [AssetInfo; 2],
+    pub start_weights: [Decimal256; 2],
+    pub end_weights: [Decimal256; 2],
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PoolResponse {
+    pub assets: [Asset; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WeightsResponse {
+    pub weights: [Decimal256; 2],
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
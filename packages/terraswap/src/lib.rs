@@ -1,8 +1,13 @@
 pub mod asset;
+pub mod dca;
 pub mod factory;
+pub mod fee_collector;
+pub mod lbp;
+pub mod limit_order;
 pub mod pair;
 pub mod querier;
 pub mod router;
+pub mod staking;
 pub mod token;
 pub mod util;
 
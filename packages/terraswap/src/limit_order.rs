@@ -0,0 +1,120 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub terraswap_factory: String,
+    /// Share of the filled ask amount paid to the executor of an order
+    pub keeper_tip: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Escrow a native `offer_asset` until the pair pays at least
+    /// `price` of `ask_asset_info` per offer unit
+    SubmitOrder {
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+        price: Decimal,
+        /// Fee tier of the pair in basis points, the default tier when unset
+        #[serde(default)]
+        fee_tier: Option<u16>,
+    },
+    /// The maker cancels the order and gets the escrowed asset back
+    CancelOrder {
+        order_id: u64,
+    },
+    /// Anyone can fill the orders whose limit price is met,
+    /// the others are skipped
+    Execute {
+        order_ids: Vec<u64>,
+    },
+    UpdateConfig {
+        owner: Option<String>,
+        keeper_tip: Option<Decimal>,
+    },
+
+    /// Internal use
+    /// Swap the order through its pair if the simulation meets the limit price
+    FillOrder {
+        order_id: u64,
+        keeper: String,
+    },
+    /// Internal use
+    /// Pay out the swapped amount to the maker and the keeper
+    SettleOrder {
+        order_id: u64,
+        keeper: String,
+        prev_ask_balance: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    SubmitOrder {
+        ask_asset_info: AssetInfo,
+        price: Decimal,
+        #[serde(default)]
+        fee_tier: Option<u16>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Order {
+        order_id: u64,
+    },
+    /// Orders of the maker ordered by id
+    Orders {
+        maker: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Orders selling `offer_asset_info` for `ask_asset_info`,
+    /// from the lowest limit price
+    OrderBook {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+        #[serde(default)]
+        fee_tier: Option<u16>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub terraswap_factory: String,
+    pub keeper_tip: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OrderResponse {
+    pub order_id: u64,
+    pub maker: String,
+    pub pair_addr: String,
+    pub offer_asset: Asset,
+    pub ask_asset_info: AssetInfo,
+    pub price: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OrdersResponse {
+    pub orders: Vec<OrderResponse>,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -1,16 +1,21 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Coin, ContractInfoResponse, ContractResult, Decimal,
+    Decimal256, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult,
+    Uint128, WasmQuery,
 };
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::panic;
 
 use crate::asset::{AssetInfo, PairInfo};
-use crate::factory::{NativeTokenDecimalsResponse, QueryMsg as FactoryQueryMsg};
+use crate::factory::{
+    ConfigResponse as FactoryConfigResponse, NativeTokenDecimalsResponse,
+    QueryMsg as FactoryQueryMsg,
+};
 use crate::pair::QueryMsg as PairQueryMsg;
-use crate::pair::{ReverseSimulationResponse, SimulationResponse};
+use crate::pair::{PoolResponse, PriceResponse, ReverseSimulationResponse, SimulationResponse};
+use crate::util::DEFAULT_FEE_TIER;
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 use std::iter::FromIterator;
@@ -35,6 +40,9 @@ pub struct WasmMockQuerier {
     base: MockQuerier,
     token_querier: TokenQuerier,
     terraswap_factory_querier: TerraswapFactoryQuerier,
+    code_ids: HashMap<String, u64>,
+    admins: HashMap<String, String>,
+    pools: HashMap<String, PoolResponse>,
 }
 
 #[derive(Clone, Default)]
@@ -70,6 +78,8 @@ pub(crate) fn balances_to_map(
 pub struct TerraswapFactoryQuerier {
     pairs: HashMap<String, PairInfo>,
     native_token_decimals: HashMap<String, u8>,
+    max_referral_commission: Decimal,
+    paused: bool,
 }
 
 impl TerraswapFactoryQuerier {
@@ -77,6 +87,8 @@ impl TerraswapFactoryQuerier {
         TerraswapFactoryQuerier {
             pairs: pairs_to_map(pairs),
             native_token_decimals: native_token_decimals_to_map(native_token_decimals),
+            max_referral_commission: Decimal::zero(),
+            paused: false,
         }
     }
 }
@@ -86,7 +98,10 @@ pub(crate) fn pairs_to_map(pairs: &[(&String, &PairInfo)]) -> HashMap<String, Pa
     for (key, pair) in pairs.iter() {
         let mut sort_key: Vec<char> = key.chars().collect();
         sort_key.sort_by(|a, b| b.cmp(a));
-        pairs_map.insert(String::from_iter(sort_key.iter()), (**pair).clone());
+        pairs_map.insert(
+            format!("{}{}", String::from_iter(sort_key.iter()), pair.fee_tier),
+            (**pair).clone(),
+        );
     }
     pairs_map
 }
@@ -122,15 +137,18 @@ impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_binary(msg) {
-                Ok(FactoryQueryMsg::Pair { asset_infos }) => {
+                Ok(FactoryQueryMsg::Pair {
+                    asset_infos,
+                    fee_tier,
+                }) => {
                     let key = [asset_infos[0].to_string(), asset_infos[1].to_string()].join("");
                     let mut sort_key: Vec<char> = key.chars().collect();
                     sort_key.sort_by(|a, b| b.cmp(a));
-                    match self
-                        .terraswap_factory_querier
-                        .pairs
-                        .get(&String::from_iter(sort_key.iter()))
-                    {
+                    match self.terraswap_factory_querier.pairs.get(&format!(
+                        "{}{}",
+                        String::from_iter(sort_key.iter()),
+                        fee_tier.unwrap_or(DEFAULT_FEE_TIER)
+                    )) {
                         Some(v) => SystemResult::Ok(ContractResult::Ok(to_binary(v).unwrap())),
                         None => SystemResult::Err(SystemError::InvalidRequest {
                             error: "No pair info exists".to_string(),
@@ -138,6 +156,25 @@ impl WasmMockQuerier {
                         }),
                     }
                 }
+                Ok(FactoryQueryMsg::Config {}) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&FactoryConfigResponse {
+                        owner: "owner0000".to_string(),
+                        pending_owner: None,
+                        pair_code_id: 321u64,
+                        token_code_id: 123u64,
+                        max_referral_commission: self
+                            .terraswap_factory_querier
+                            .max_referral_commission,
+                        timelock_delay: 0,
+                        creation_fee: None,
+                        fee_recipient: None,
+                        permissionless: true,
+                        restrict_assets: false,
+                        guardian: None,
+                        paused: self.terraswap_factory_querier.paused,
+                    })
+                    .unwrap(),
+                )),
                 Ok(FactoryQueryMsg::NativeTokenDecimals { denom }) => {
                     match self
                         .terraswap_factory_querier
@@ -157,8 +194,14 @@ impl WasmMockQuerier {
                     }
                 }
                 _ => match from_binary(msg) {
-                    Ok(PairQueryMsg::Pair {}) => {
-                        SystemResult::Ok(ContractResult::from(to_binary(&PairInfo {
+                    Ok(PairQueryMsg::Pair {}) => match self
+                        .terraswap_factory_querier
+                        .pairs
+                        .values()
+                        .find(|pair| pair.contract_addr == *contract_addr)
+                    {
+                        Some(pair) => SystemResult::Ok(ContractResult::from(to_binary(pair))),
+                        None => SystemResult::Ok(ContractResult::from(to_binary(&PairInfo {
                             asset_infos: [
                                 AssetInfo::NativeToken {
                                     denom: "uluna".to_string(),
@@ -170,13 +213,31 @@ impl WasmMockQuerier {
                             asset_decimals: [6u8, 6u8],
                             contract_addr: "pair0000".to_string(),
                             liquidity_token: "liquidity0000".to_string(),
-                        })))
-                    }
-                    Ok(PairQueryMsg::Simulation { offer_asset }) => {
+                            fee_tier: DEFAULT_FEE_TIER,
+                        }))),
+                    },
+                    Ok(PairQueryMsg::Pool {}) => match self.pools.get(contract_addr) {
+                        Some(pool) => SystemResult::Ok(ContractResult::from(to_binary(pool))),
+                        None => SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_string(),
+                        }),
+                    },
+                    Ok(PairQueryMsg::Simulation {
+                        offer_asset,
+                        referral_commission,
+                    }) => {
+                        let referral_amount =
+                            offer_asset.amount * referral_commission.unwrap_or_default();
                         SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
-                            return_amount: offer_asset.amount,
+                            return_amount: offer_asset.amount - referral_amount,
                             commission_amount: Uint128::zero(),
                             spread_amount: Uint128::zero(),
+                            referral_amount,
+                        })))
+                    }
+                    Ok(PairQueryMsg::Price { .. }) => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&PriceResponse {
+                            price: Decimal256::percent(150),
                         })))
                     }
                     Ok(PairQueryMsg::ReverseSimulation { ask_asset }) => SystemResult::Ok(
@@ -254,6 +315,20 @@ impl WasmMockQuerier {
                     },
                 },
             },
+            QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr }) => {
+                match self.code_ids.get(contract_addr) {
+                    Some(code_id) => {
+                        let mut res = ContractInfoResponse::default();
+                        res.code_id = *code_id;
+                        res.creator = "creator".to_string();
+                        res.admin = self.admins.get(contract_addr).cloned();
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                    }
+                    None => SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr.to_string(),
+                    }),
+                }
+            }
             _ => self.base.handle_query(request),
         }
     }
@@ -265,6 +340,9 @@ impl WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             terraswap_factory_querier: TerraswapFactoryQuerier::default(),
+            code_ids: HashMap::new(),
+            admins: HashMap::new(),
+            pools: HashMap::new(),
         }
     }
 
@@ -282,6 +360,37 @@ impl WasmMockQuerier {
         self.terraswap_factory_querier = TerraswapFactoryQuerier::new(pairs, native_token_decimals);
     }
 
+    pub fn with_max_referral_commission(&mut self, max_referral_commission: Decimal) {
+        self.terraswap_factory_querier.max_referral_commission = max_referral_commission;
+    }
+
+    pub fn with_factory_paused(&mut self, paused: bool) {
+        self.terraswap_factory_querier.paused = paused;
+    }
+
+    // configure the code ids returned by the contract info query
+    pub fn with_code_ids(&mut self, code_ids: &[(&String, u64)]) {
+        for (contract_addr, code_id) in code_ids {
+            self.code_ids.insert(contract_addr.to_string(), *code_id);
+        }
+    }
+
+    // configure the admins returned by the contract info query
+    pub fn with_admins(&mut self, admins: &[(&String, &String)]) {
+        for (contract_addr, admin) in admins {
+            self.admins
+                .insert(contract_addr.to_string(), admin.to_string());
+        }
+    }
+
+    // configure the reserves returned by the pair pool query
+    pub fn with_pools(&mut self, pools: &[(&String, &PoolResponse)]) {
+        for (contract_addr, pool) in pools {
+            self.pools
+                .insert(contract_addr.to_string(), (*pool).clone());
+        }
+    }
+
     pub fn with_balance(&mut self, balances: &[(&String, Vec<Coin>)]) {
         for (addr, balance) in balances {
             self.base.update_balance(addr.to_string(), balance.clone());
@@ -320,6 +429,7 @@ mod mock_exception {
                     denom: "ulunc".to_string(),
                 },
             ],
+            fee_tier: None,
         })
         .unwrap();
         assert_eq!(
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::asset::{Asset, AssetInfo};
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Decimal, Decimal256, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -13,6 +13,8 @@ pub struct InstantiateMsg {
     /// Token contract code id for initialization
     pub token_code_id: u64,
     pub asset_decimals: [u8; 2],
+    /// Commission rate in basis points, 0.3% when unset
+    pub fee_tier: Option<u16>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -33,9 +35,29 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         to: Option<String>,
         deadline: Option<u64>,
+        /// `referral_commission` of the offer amount is sent to `referral_address`
+        /// before the swap, capped by the factory `max_referral_commission`
+        referral_address: Option<String>,
+        referral_commission: Option<Decimal>,
+    },
+    /// Enables (or disables with `None`) the volatility based commission,
+    /// only the factory owner can execute it
+    UpdateDynamicFee {
+        dynamic_fee: Option<DynamicFeeConfig>,
     },
 }
 
+/// The commission rate follows an EMA of the relative pool price moves between blocks:
+/// commission_rate = min(min_commission_rate + volatility_multiplier * volatility, max_commission_rate)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DynamicFeeConfig {
+    pub min_commission_rate: Decimal256,
+    pub max_commission_rate: Decimal256,
+    pub volatility_multiplier: Decimal256,
+    /// Weight of the latest price move in the EMA, in (0, 1]
+    pub smoothing_factor: Decimal256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
@@ -45,6 +67,8 @@ pub enum Cw20HookMsg {
         max_spread: Option<Decimal>,
         to: Option<String>,
         deadline: Option<u64>,
+        referral_address: Option<String>,
+        referral_commission: Option<Decimal>,
     },
     WithdrawLiquidity {
         min_assets: Option<[Asset; 2]>,
@@ -57,8 +81,26 @@ pub enum Cw20HookMsg {
 pub enum QueryMsg {
     Pair {},
     Pool {},
-    Simulation { offer_asset: Asset },
-    ReverseSimulation { ask_asset: Asset },
+    Simulation {
+        offer_asset: Asset,
+        referral_commission: Option<Decimal>,
+    },
+    ReverseSimulation {
+        ask_asset: Asset,
+    },
+    /// Marginal price of `base_asset` in terms of the other asset,
+    /// adjusted by the asset decimals
+    Price {
+        base_asset: AssetInfo,
+    },
+    /// Manipulation resistant value of one liquidity token unit, computed from
+    /// `prices` (in pair asset order, per smallest asset unit) and the invariant
+    /// instead of the current reserves
+    LpTokenValue {
+        prices: [Decimal256; 2],
+    },
+    /// Dynamic fee config and the commission rate the next swap would pay
+    DynamicFee {},
 }
 
 // We define a custom struct for each query response
@@ -74,6 +116,8 @@ pub struct SimulationResponse {
     pub return_amount: Uint128,
     pub spread_amount: Uint128,
     pub commission_amount: Uint128,
+    #[serde(default)]
+    pub referral_amount: Uint128,
 }
 
 /// ReverseSimulationResponse returns reverse swap simulation response
@@ -84,6 +128,26 @@ pub struct ReverseSimulationResponse {
     pub commission_amount: Uint128,
 }
 
+/// PriceResponse returns the spot price without commission and spread
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PriceResponse {
+    pub price: Decimal256,
+}
+
+/// LpTokenValueResponse returns the fair value of one liquidity token unit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LpTokenValueResponse {
+    pub value: Decimal256,
+}
+
+/// DynamicFeeResponse returns the dynamic fee config with the current volatility estimate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DynamicFeeResponse {
+    pub dynamic_fee: Option<DynamicFeeConfig>,
+    pub volatility: Decimal256,
+    pub commission_rate: Decimal256,
+}
+
 /// We currently take no arguments for migrations
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -1,9 +1,15 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
-use crate::factory::{NativeTokenDecimalsResponse, QueryMsg as FactoryQueryMsg};
-use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
+use crate::factory::{
+    ConfigResponse as FactoryConfigResponse, NativeTokenDecimalsResponse,
+    QueryMsg as FactoryQueryMsg,
+};
+use crate::pair::{
+    PoolResponse, PriceResponse, QueryMsg as PairQueryMsg, ReverseSimulationResponse,
+    SimulationResponse,
+};
 
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, QuerierWrapper,
+    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, Decimal, QuerierWrapper,
     QueryRequest, StdResult, Uint128, WasmQuery,
 };
 
@@ -72,15 +78,28 @@ pub fn query_native_decimals(
     Ok(res.decimals)
 }
 
+pub fn query_factory_config(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+) -> StdResult<FactoryConfigResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::Config {})?,
+    }))
+}
+
+/// The pair of the fee tier, the default one when unset
 pub fn query_pair_info(
     querier: &QuerierWrapper,
     factory_contract: Addr,
     asset_infos: &[AssetInfo; 2],
+    fee_tier: Option<u16>,
 ) -> StdResult<PairInfo> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: factory_contract.to_string(),
         msg: to_binary(&FactoryQueryMsg::Pair {
             asset_infos: asset_infos.clone(),
+            fee_tier,
         })?,
     }))
 }
@@ -89,11 +108,13 @@ pub fn simulate(
     querier: &QuerierWrapper,
     pair_contract: Addr,
     offer_asset: &Asset,
+    referral_commission: Option<Decimal>,
 ) -> StdResult<SimulationResponse> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: pair_contract.to_string(),
         msg: to_binary(&PairQueryMsg::Simulation {
             offer_asset: offer_asset.clone(),
+            referral_commission,
         })?,
     }))
 }
@@ -111,6 +132,19 @@ pub fn reverse_simulate(
     }))
 }
 
+pub fn query_price(
+    querier: &QuerierWrapper,
+    pair_contract: Addr,
+    base_asset: &AssetInfo,
+) -> StdResult<PriceResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairQueryMsg::Price {
+            base_asset: base_asset.clone(),
+        })?,
+    }))
+}
+
 pub fn query_pair_info_from_pair(
     querier: &QuerierWrapper,
     pair_contract: Addr,
@@ -122,3 +156,10 @@ pub fn query_pair_info_from_pair(
 
     Ok(pair_info)
 }
+
+pub fn query_pool(querier: &QuerierWrapper, pair_contract: Addr) -> StdResult<PoolResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairQueryMsg::Pool {})?,
+    }))
+}
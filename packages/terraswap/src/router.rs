@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 use crate::asset::AssetInfo;
@@ -17,6 +17,9 @@ pub enum SwapOperation {
     TerraSwap {
         offer_asset_info: AssetInfo,
         ask_asset_info: AssetInfo,
+        /// Fee tier of the pair in basis points, the default tier when unset
+        #[serde(default)]
+        fee_tier: Option<u16>,
     },
 }
 
@@ -38,6 +41,9 @@ pub enum ExecuteMsg {
         minimum_receive: Option<Uint128>,
         to: Option<String>,
         deadline: Option<u64>,
+        /// The referral commission is taken from the first operation
+        referral_address: Option<String>,
+        referral_commission: Option<Decimal>,
     },
 
     /// Internal use
@@ -46,6 +52,8 @@ pub enum ExecuteMsg {
         operation: SwapOperation,
         to: Option<String>,
         deadline: Option<u64>,
+        referral_address: Option<String>,
+        referral_commission: Option<Decimal>,
     },
     /// Internal use
     /// Check the swap amount is exceed minimum_receive
@@ -65,6 +73,9 @@ pub enum Cw20HookMsg {
         minimum_receive: Option<Uint128>,
         to: Option<String>,
         deadline: Option<u64>,
+        /// The referral commission is taken from the first operation
+        referral_address: Option<String>,
+        referral_commission: Option<Decimal>,
     },
 }
 
@@ -75,11 +86,18 @@ pub enum QueryMsg {
     SimulateSwapOperations {
         offer_amount: Uint128,
         operations: Vec<SwapOperation>,
+        /// The referral commission is taken from the first operation
+        referral_commission: Option<Decimal>,
     },
     ReverseSimulateSwapOperations {
         ask_amount: Uint128,
         operations: Vec<SwapOperation>,
     },
+    /// Spot price of the first offer asset in terms of the last ask asset,
+    /// composed from the pair prices along the route
+    Price {
+        operations: Vec<SwapOperation>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -0,0 +1,104 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::{Decimal256, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// LP token contract which is accepted for bonding
+    pub staking_token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Unbond staked LP tokens and send them back to the sender
+    Unbond {
+        amount: Uint128,
+    },
+    /// Withdraw all pending rewards
+    Withdraw {},
+    /// Owner registers a new reward schedule.
+    /// The native reward must be sent along with the message and
+    /// the cw20 reward must be approved to the staking contract.
+    AddDistributionSchedule {
+        asset: Asset,
+        start_time: u64,
+        end_time: u64,
+    },
+    /// Owner takes the rewards distributed while nothing was bonded
+    SweepUnclaimedRewards {
+        asset_info: AssetInfo,
+    },
+    UpdateConfig {
+        owner: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Bond the sent LP tokens
+    Bond {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    State {},
+    StakerInfo { staker: String },
+    Pending { staker: String },
+}
+
+/// DistributionSchedule distributes `amount` linearly
+/// from `start_time` to `end_time`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DistributionSchedule {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub amount: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub staking_token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RewardInfoResponse {
+    pub info: AssetInfo,
+    pub global_index: Decimal256,
+    /// The schedules left to distribute
+    pub distribution_schedules: Vec<DistributionSchedule>,
+    pub unclaimed_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StateResponse {
+    pub total_bond_amount: Uint128,
+    pub last_distributed: u64,
+    pub rewards: Vec<RewardInfoResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StakerInfoResponse {
+    pub staker: String,
+    pub bond_amount: Uint128,
+    pub pending_rewards: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingResponse {
+    pub rewards: Vec<Asset>,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -394,6 +394,7 @@ fn query_terraswap_pair_contract() {
                 contract_addr: "pair0000".to_string(),
                 liquidity_token: "liquidity0000".to_string(),
                 asset_decimals: [6u8, 6u8],
+                fee_tier: 30,
             },
         )],
         &[("uusd".to_string(), 6u8)],
@@ -410,6 +411,7 @@ fn query_terraswap_pair_contract() {
                 denom: "uusd".to_string(),
             },
         ],
+        None,
     )
     .unwrap();
 
@@ -1,5 +1,12 @@
-use cosmwasm_std::{DepsMut, StdError, StdResult};
+use cosmwasm_std::{
+    instantiate2_address, Binary, CanonicalAddr, ConversionOverflowError, Decimal256, DepsMut,
+    StdError, StdResult, Uint128, Uint256,
+};
 use cw2::{get_contract_version, set_contract_version};
+use sha2::{Digest, Sha256};
+use std::convert::TryInto;
+
+use crate::asset::AssetInfoRaw;
 
 pub fn migrate_version(
     deps: DepsMut,
@@ -24,6 +31,98 @@ pub fn migrate_version(
     Ok(())
 }
 
+/// Commission rates pairs can be created with, in basis points
+pub const FEE_TIERS: [u16; 4] = [1, 5, 30, 100];
+/// The 0.3% commission of the pairs created before the fee tiers
+pub const DEFAULT_FEE_TIER: u16 = 30;
+
+pub fn default_fee_tier() -> u16 {
+    DEFAULT_FEE_TIER
+}
+
+/// The commission rate of a fee tier
+pub fn fee_tier_rate(fee_tier: u16) -> Decimal256 {
+    Decimal256::from_ratio(fee_tier, 10_000u16)
+}
+
+/// The registry key of the asset pair, independent of the asset order
+pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
+    let mut asset_infos = asset_infos.to_vec();
+    asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+
+    [asset_infos[0].as_bytes(), asset_infos[1].as_bytes()].concat()
+}
+
+/// The registry key of a pair in a fee tier. The default tier keeps the bare `pair_key`
+/// of the pairs created before the fee tiers, the other tiers sort right after it
+pub fn fee_tier_pair_key(asset_infos: &[AssetInfoRaw; 2], fee_tier: u16) -> Vec<u8> {
+    let mut key = pair_key(asset_infos);
+    if fee_tier != DEFAULT_FEE_TIER {
+        key.push(0);
+        key.extend_from_slice(&fee_tier.to_be_bytes());
+    }
+
+    key
+}
+
+/// The instantiate2 salt of a pair, the pair key is hashed to fit the salt length limit.
+/// `generation` is the number of times the pair key was deregistered, so a pair created
+/// again after a deregistration does not collide with the address of the previous one.
+pub fn pair_salt(pair_key: &[u8], generation: u32) -> Binary {
+    if generation == 0 {
+        return Binary::from(Sha256::digest(pair_key).to_vec());
+    }
+
+    Binary::from(Sha256::digest([pair_key, &generation.to_be_bytes()].concat()).to_vec())
+}
+
+/// Computes the address the factory instantiates the pair at, without querying the chain.
+/// `pair_code_checksum` is the checksum of the factory `pair_code_id`, `generation` is the
+/// number of times the pair key was deregistered and the result is humanized with the `Api`
+/// of the chain.
+pub fn compute_pair_address(
+    factory: &CanonicalAddr,
+    pair_code_checksum: &[u8],
+    pair_key: &[u8],
+    generation: u32,
+) -> StdResult<CanonicalAddr> {
+    instantiate2_address(
+        pair_code_checksum,
+        factory,
+        pair_salt(pair_key, generation).as_slice(),
+    )
+    .map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+/// LP tokens locked in the pair on the initial provision
+pub const MINIMUM_LIQUIDITY_AMOUNT: u128 = 1_000;
+
+/// The share of the initial provision, sqrt(deposit_0 * deposit_1),
+/// before MINIMUM_LIQUIDITY_AMOUNT is deducted
+pub fn compute_initial_share(deposits: [Uint128; 2]) -> Result<Uint128, ConversionOverflowError> {
+    let deposit0: Uint256 = deposits[0].into();
+    let deposit1: Uint256 = deposits[1].into();
+
+    (Decimal256::from_ratio(deposit0 * deposit1, 1u8).sqrt() * Uint256::from(1u8)).try_into()
+}
+
+/// wasmd rejects contract labels longer than this
+pub const MAX_LABEL_LENGTH: usize = 128;
+
+/// `{prefix} {symbol}-{symbol}`, truncated to `MAX_LABEL_LENGTH` bytes on a char boundary
+pub fn pair_label(prefix: &str, symbols: &[String; 2]) -> String {
+    let mut label = format!("{} {}-{}", prefix, symbols[0], symbols[1]);
+    if label.len() > MAX_LABEL_LENGTH {
+        let mut end = MAX_LABEL_LENGTH;
+        while !label.is_char_boundary(end) {
+            end -= 1;
+        }
+        label.truncate(end);
+    }
+
+    label
+}
+
 #[cfg(test)]
 mod test {
     use crate::mock_querier::mock_dependencies;
@@ -92,4 +191,84 @@ mod test {
 
         assert_eq!(version.version, TARGET_VERSION);
     }
+
+    #[test]
+    pub fn fee_tier_pair_keys() {
+        let deps = mock_dependencies(&[]);
+        let asset_infos = [
+            AssetInfoRaw::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            AssetInfoRaw::Token {
+                contract_addr: deps.as_ref().api.addr_canonicalize("asset0000").unwrap(),
+            },
+        ];
+
+        let key = pair_key(&asset_infos);
+        assert_eq!(fee_tier_pair_key(&asset_infos, DEFAULT_FEE_TIER), key);
+
+        // the other tiers sort between the default tier and the next asset pair
+        let mut next = key.clone();
+        next.push(1);
+        for fee_tier in [1u16, 5, 100] {
+            let tier_key = fee_tier_pair_key(&asset_infos, fee_tier);
+            assert!(key < tier_key && tier_key < next);
+        }
+
+        assert_eq!(fee_tier_rate(DEFAULT_FEE_TIER), Decimal256::permille(3));
+    }
+
+    #[test]
+    pub fn pair_address_ignores_asset_order() {
+        let deps = mock_dependencies(&[]);
+        let asset_infos = [
+            AssetInfoRaw::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            AssetInfoRaw::Token {
+                contract_addr: deps.as_ref().api.addr_canonicalize("asset0000").unwrap(),
+            },
+        ];
+        let reversed = [asset_infos[1].clone(), asset_infos[0].clone()];
+        let factory = deps.as_ref().api.addr_canonicalize("factory0000").unwrap();
+        let checksum = [1u8; 32];
+
+        let address =
+            compute_pair_address(&factory, &checksum, &pair_key(&asset_infos), 0).unwrap();
+        assert_eq!(
+            address,
+            compute_pair_address(&factory, &checksum, &pair_key(&reversed), 0).unwrap()
+        );
+        assert_eq!(
+            address,
+            instantiate2_address(&checksum, &factory, &Sha256::digest(pair_key(&asset_infos)))
+                .unwrap()
+        );
+
+        // a recreated pair gets a new address
+        assert_ne!(
+            address,
+            compute_pair_address(&factory, &checksum, &pair_key(&asset_infos), 1).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn pair_label_is_truncated_on_char_boundary() {
+        assert_eq!(
+            pair_label("terraswap pair", &["uluna".to_string(), "ANC".to_string()]),
+            "terraswap pair uluna-ANC"
+        );
+
+        let ibc_denom = format!("ibc/{}", "A".repeat(64));
+        let label = pair_label("terraswap pair", &[ibc_denom.clone(), ibc_denom]);
+        assert_eq!(label.len(), MAX_LABEL_LENGTH);
+
+        // a multi-byte char crossing the limit is dropped entirely
+        let label = pair_label(
+            "terraswap pair",
+            &["a".repeat(MAX_LABEL_LENGTH - 17), "éé".to_string()],
+        );
+        assert_eq!(label.len(), MAX_LABEL_LENGTH - 1);
+        assert!(label.ends_with('-'));
+    }
 }